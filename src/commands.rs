@@ -0,0 +1,144 @@
+//! Subcommands that read register description files without generating code.
+use crate::rust_gen::{check_device, normalized_svd, read_register_description, ReadSettings};
+use crate::{
+    svd, svd_diff, InfoArgs, InputArgs, PatchArgs, ReportFormat, SvdDiffArgs, ValidateArgs,
+};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+impl InputArgs {
+    fn read_settings(&self, patch: Option<PathBuf>, sha256: Option<String>) -> ReadSettings {
+        ReadSettings {
+            input_format: self.input_format,
+            pack_device: self.pack_device.clone(),
+            sha256,
+            patch,
+            svd_validation_level: self.svd_validation_level,
+        }
+    }
+}
+
+/// Read register description file and check conversion to code generation model
+pub(crate) fn validate(args: &ValidateArgs) -> Result<()> {
+    let path = &args.register_description_file_name;
+    let (mut device, _, _) = read_register_description(
+        path,
+        &args
+            .input
+            .read_settings(args.patch.clone(), args.sha256.clone()),
+    )?;
+    // Registers added by patch inherit size, access and reset value
+    svd_parser::expand_properties(&mut device);
+    check_device(
+        &device,
+        args.address_block_check,
+        args.aliased_register_check,
+    )?;
+    println!(
+        "{} is a valid register description of {}",
+        path.display(),
+        device.name
+    );
+    Ok(())
+}
+
+/// Print changes of peripherals, registers, fields and interrupts between two register description files
+pub(crate) fn diff(args: &SvdDiffArgs) -> Result<()> {
+    let read = |path: &Path| {
+        read_register_description(path, &args.input.read_settings(args.patch.clone(), None))
+            .map(|(device, _, _)| device)
+    };
+    let old_device = read(&args.old_file_name)?;
+    let new_device = read(&args.new_file_name)?;
+    let changes = svd_diff::diff_devices(&old_device, &new_device)?;
+    match args.format {
+        ReportFormat::Text => print!("{}", svd_diff::text_report(&changes)?),
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+    }
+    Ok(())
+}
+
+/// Write normalized SVD of patched register description file
+pub(crate) fn patch(args: &PatchArgs) -> Result<()> {
+    let (device, _, _) = read_register_description(
+        &args.register_description_file_name,
+        &args
+            .input
+            .read_settings(Some(args.patch_file_name.clone()), None),
+    )?;
+    let svd = normalized_svd(&device);
+    match args.output {
+        Some(ref path) => fs::write(path, svd)
+            .with_context(|| format!("Cannot write patched SVD file {}", path.display()))?,
+        None => print!("{svd}"),
+    }
+    Ok(())
+}
+
+/// Number of registers and fields of registers and clusters
+fn count_registers(registers: &[svd::RegisterCluster]) -> (usize, usize) {
+    let mut count = (0, 0);
+    for register in registers {
+        let (registers, fields) = match register {
+            svd::RegisterCluster::Register(register) => {
+                (1, register.fields.iter().flatten().count())
+            }
+            svd::RegisterCluster::Cluster(cluster) => count_registers(&cluster.children),
+        };
+        count.0 += registers;
+        count.1 += fields;
+    }
+    count
+}
+
+/// Print summary of device, peripherals and interrupts
+pub(crate) fn info(args: &InfoArgs) -> Result<()> {
+    let (device, _, _) = read_register_description(
+        &args.register_description_file_name,
+        &args.input.read_settings(args.patch.clone(), None),
+    )?;
+    // Registers of derived peripherals are counted as the ones of their base
+    let device = svd_parser::expand(&device)?;
+
+    let mut peripherals = String::new();
+    let mut interrupts = BTreeMap::new();
+    let (mut register_count, mut field_count) = (0, 0);
+    for peripheral in &device.peripherals {
+        let (registers, fields) = count_registers(peripheral.registers.as_deref().unwrap_or(&[]));
+        register_count += registers;
+        field_count += fields;
+        for interrupt in &peripheral.interrupt {
+            interrupts.insert(interrupt.value, interrupt.name.clone());
+        }
+        writeln!(
+            peripherals,
+            "  {:<24} 0x{:08x} {registers:>5} registers",
+            peripheral.name, peripheral.base_address
+        )?;
+    }
+
+    println!("Device {} {}", device.name, device.version);
+    if !device.description.trim().is_empty() {
+        println!("{}", device.description.trim());
+    }
+    if let Some(ref cpu) = device.cpu {
+        println!("CPU {} revision {}", cpu.name, cpu.revision);
+    }
+    println!(
+        "{} peripherals, {register_count} registers, {field_count} fields, {} interrupts",
+        device.peripherals.len(),
+        interrupts.len()
+    );
+    println!("Peripherals:");
+    print!("{peripherals}");
+    if !interrupts.is_empty() {
+        println!("Interrupts:");
+        for (value, name) in &interrupts {
+            println!("  {value:>4} {name}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,392 @@
+//! TOML configuration file driving code generation.
+//!
+//! The file holds the command line options and the settings that don't fit flags:
+//! renames of peripherals, clusters, registers and bitfields, peripheral filters and
+//! Cargo features enabling groups of peripherals.
+//!
+//! # Example
+//!
+//! ```toml
+//! register-description-file = "svd/device.svd"
+//! destination-folder = "device-pac"
+//!
+//! # Command line options by long name
+//! [options]
+//! target = "cortex-m"
+//! tracing = true
+//! package-name = "device-pac"
+//!
+//! # New names by path of peripheral, cluster, register and bitfield as in register description file
+//! [renames]
+//! "UART0" = "SERIAL0"
+//! "TIMER.CTRL.EN" = "ENABLE"
+//!
+//! # Peripherals included in or excluded from the PAC, names can be globs
+//! [filter]
+//! exclude = ["DEBUG*"]
+//!
+//! # Cargo features enabling groups of peripherals
+//! [features]
+//! serial = ["SERIAL0", "UART1"]
+//! ```
+use crate::rust_gen::glob_to_regex;
+use crate::{svd, Args, Transform};
+use anyhow::{anyhow, Context, Result};
+use clap::CommandFactory;
+use lazy_regex::Regex;
+use log::info;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Content of configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    /// Used if register description file is not given in command line
+    register_description_file: Option<PathBuf>,
+    /// Used if destination folder is not given in command line
+    destination_folder: Option<PathBuf>,
+    /// Command line options by long name, `-` and `_` are equivalent
+    #[serde(default)]
+    options: toml::Table,
+    /// New names by path, e.g. `TIMER.CTRL.EN`
+    #[serde(default)]
+    renames: BTreeMap<String, String>,
+    #[serde(default)]
+    filter: Filter,
+    /// Cargo features enabling groups of peripherals
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+}
+
+/// Peripherals included in or excluded from generation
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Filter {
+    /// If not empty only matching peripherals are generated
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+impl Config {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read configuration file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration file {}", path.display()))
+    }
+
+    /// Transformations of device description defined by configuration file.
+    /// Peripherals are filtered by their name in register description file before renaming.
+    pub(crate) fn transforms(&self) -> Result<Vec<Box<dyn Transform>>> {
+        let mut result: Vec<Box<dyn Transform>> = Vec::new();
+        if !self.filter.include.is_empty() || !self.filter.exclude.is_empty() {
+            let patterns = |globs: &[String]| -> Result<Vec<Regex>> {
+                globs.iter().map(|glob| glob_to_regex(glob)).collect()
+            };
+            result.push(Box::new(PeripheralFilter {
+                include: patterns(&self.filter.include)?,
+                exclude: patterns(&self.filter.exclude)?,
+            }));
+        }
+        if !self.renames.is_empty() {
+            result.push(Box::new(Renames(self.renames.clone())));
+        }
+        Ok(result)
+    }
+
+    /// Names of peripherals enabled by each feature group
+    pub(crate) fn feature_groups(&self) -> BTreeMap<String, Vec<String>> {
+        self.features.clone()
+    }
+}
+
+/// Check if long option of command line takes a value
+fn takes_value(long: &str) -> Option<bool> {
+    Args::command()
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+        .map(|arg| arg.get_action().takes_values())
+}
+
+/// Check if long option of command line takes its value from next argument
+fn takes_next_value(long: &str) -> bool {
+    Args::command()
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+        .is_some_and(|arg| arg.get_action().takes_values() && !arg.is_require_equals_set())
+}
+
+/// Path of configuration file given with `--config`
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let Some(arg) = arg.to_str() else {
+            continue;
+        };
+        if arg == "--config" {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Number of positional arguments in command line
+fn count_positionals(args: &[OsString]) -> usize {
+    let mut count = 0;
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.to_str() {
+            Some("--") => return count + iter.count(),
+            Some(arg) if arg.starts_with("--") => {
+                if !arg.contains('=') && takes_next_value(&arg[2..]) {
+                    iter.next();
+                }
+            }
+            Some(arg) if arg.starts_with('-') && arg != "-" => {}
+            _ => count += 1,
+        }
+    }
+    count
+}
+
+/// Convert option of configuration file to command line arguments
+fn option_args(key: &str, value: &toml::Value) -> Result<Vec<OsString>> {
+    let long = key.replace('_', "-");
+    let takes_value = match long.as_str() {
+        "config" => {
+            return Err(anyhow!(
+                "Option config is not allowed in configuration file"
+            ))
+        }
+        long => takes_value(long)
+            .ok_or_else(|| anyhow!("Unknown option {key} in configuration file"))?,
+    };
+    let values = match value {
+        toml::Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    let mut result = Vec::new();
+    for value in values {
+        let value = match value {
+            toml::Value::Boolean(flag) if !takes_value => {
+                if *flag {
+                    result.push(format!("--{long}").into());
+                }
+                continue;
+            }
+            toml::Value::String(value) if takes_value => value.clone(),
+            toml::Value::Integer(value) if takes_value => value.to_string(),
+            value => {
+                return Err(anyhow!(
+                    "Invalid value {value} of option {key} in configuration file"
+                ))
+            }
+        };
+        result.push(format!("--{long}={value}").into());
+    }
+    Ok(result)
+}
+
+/// Add arguments defined by configuration file given with `--config`.
+///
+/// Options of configuration file are inserted before the command line arguments so the ones
+/// given in command line take precedence. Register description file and destination folder of
+/// configuration file are used only if no positional argument is given in command line.
+pub(crate) fn expand_arguments(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let Some(path) = config_path(&args) else {
+        return Ok(args);
+    };
+    let config = Config::load(&path)?;
+    let mut result: Vec<OsString> = args.iter().take(1).cloned().collect();
+    for (key, value) in &config.options {
+        result.extend(option_args(key, value)?);
+    }
+    let add_positionals = count_positionals(&args) == 0;
+    result.extend(args.into_iter().skip(1));
+    if add_positionals {
+        result.extend(
+            [config.register_description_file, config.destination_folder]
+                .into_iter()
+                .flatten()
+                .map(OsString::from),
+        );
+    }
+    Ok(result)
+}
+
+/// Keep only peripherals selected by filter of configuration file
+struct PeripheralFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl Transform for PeripheralFilter {
+    fn name(&self) -> &str {
+        "peripheral filter of configuration file"
+    }
+
+    fn apply(&self, device: &mut svd::Device) -> Result<()> {
+        let count = device.peripherals.len();
+        device.peripherals.retain(|peripheral| {
+            (self.include.is_empty() || self.include.iter().any(|p| p.is_match(&peripheral.name)))
+                && !self.exclude.iter().any(|p| p.is_match(&peripheral.name))
+        });
+        // Derived peripherals need their base peripheral
+        for peripheral in &device.peripherals {
+            if let Some(base) = &peripheral.derived_from {
+                if !device.peripherals.iter().any(|p| p.name == *base) {
+                    return Err(anyhow!(
+                        "Peripheral {} is derived from {base} that is removed by filter",
+                        peripheral.name
+                    ));
+                }
+            }
+        }
+        info!(
+            "Filter of configuration file removed {} peripherals",
+            count - device.peripherals.len()
+        );
+        Ok(())
+    }
+}
+
+/// Rename items by path of configuration file
+struct Renames(BTreeMap<String, String>);
+
+impl Transform for Renames {
+    fn name(&self) -> &str {
+        "renames of configuration file"
+    }
+
+    fn apply(&self, device: &mut svd::Device) -> Result<()> {
+        for (path, new_name) in &self.0 {
+            let path: Vec<&str> = path.split('.').collect();
+            if !rename_peripherals(&mut device.peripherals, &path, new_name) {
+                return Err(anyhow!("No item {} to rename", path.join(".")));
+            }
+            // Items derived from renamed item by its full path
+            let old_path = path.join(".");
+            let new_path = [&path[..path.len() - 1], &[new_name.as_str()]]
+                .concat()
+                .join(".");
+            for peripheral in device.peripherals.iter_mut() {
+                rename_derived_path(&mut peripheral.derived_from, &old_path, &new_path);
+                if let Some(registers) = peripheral.registers.as_mut() {
+                    rename_derived_paths(registers, &old_path, &new_path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rename peripheral or one of its children, returns `false` if path is not found
+fn rename_peripherals(peripherals: &mut [svd::Peripheral], path: &[&str], new_name: &str) -> bool {
+    let mut found = false;
+    for peripheral in peripherals.iter_mut().filter(|p| p.name == path[0]) {
+        found |= match &path[1..] {
+            [] => {
+                peripheral.name = new_name.to_owned();
+                true
+            }
+            path => peripheral
+                .registers
+                .as_mut()
+                .is_some_and(|registers| rename_registers(registers, path, new_name)),
+        };
+    }
+    found
+}
+
+/// Rename cluster, register or bitfield, returns `false` if path is not found
+fn rename_registers(registers: &mut [svd::RegisterCluster], path: &[&str], new_name: &str) -> bool {
+    let mut found = false;
+    for register in registers.iter_mut() {
+        found |= match register {
+            svd::RegisterCluster::Register(register) if register.name == path[0] => {
+                match &path[1..] {
+                    [] => {
+                        register.name = new_name.to_owned();
+                        true
+                    }
+                    [field_name] => register.fields.as_mut().is_some_and(|fields| {
+                        let mut found = false;
+                        for field in fields.iter_mut().filter(|f| f.name == *field_name) {
+                            field.name = new_name.to_owned();
+                            found = true;
+                        }
+                        if found {
+                            for field in fields.iter_mut() {
+                                rename_derived_from(&mut field.derived_from, field_name, new_name);
+                            }
+                        }
+                        found
+                    }),
+                    _ => false,
+                }
+            }
+            svd::RegisterCluster::Cluster(cluster) if cluster.name == path[0] => match &path[1..] {
+                [] => {
+                    cluster.name = new_name.to_owned();
+                    true
+                }
+                path => rename_registers(&mut cluster.children, path, new_name),
+            },
+            _ => false,
+        };
+    }
+    if found && path.len() == 1 {
+        for register in registers.iter_mut() {
+            let derived_from = match register {
+                svd::RegisterCluster::Register(register) => &mut register.derived_from,
+                svd::RegisterCluster::Cluster(cluster) => &mut cluster.derived_from,
+            };
+            rename_derived_from(derived_from, path[0], new_name);
+        }
+    }
+    found
+}
+
+/// Update reference to a renamed sibling item
+fn rename_derived_from(derived_from: &mut Option<String>, old_name: &str, new_name: &str) {
+    if derived_from.as_deref() == Some(old_name) {
+        *derived_from = Some(new_name.to_owned());
+    }
+}
+
+/// Update references by full path to a renamed item or to one of its children
+fn rename_derived_path(derived_from: &mut Option<String>, old_path: &str, new_path: &str) {
+    if let Some(path) = derived_from {
+        if let Some(rest) = path.strip_prefix(old_path) {
+            if rest.is_empty() || rest.starts_with('.') {
+                *path = format!("{new_path}{rest}");
+            }
+        }
+    }
+}
+
+fn rename_derived_paths(registers: &mut [svd::RegisterCluster], old_path: &str, new_path: &str) {
+    for register in registers.iter_mut() {
+        match register {
+            svd::RegisterCluster::Register(register) => {
+                rename_derived_path(&mut register.derived_from, old_path, new_path);
+                for field in register.fields.iter_mut().flatten() {
+                    rename_derived_path(&mut field.derived_from, old_path, new_path);
+                }
+            }
+            svd::RegisterCluster::Cluster(cluster) => {
+                rename_derived_path(&mut cluster.derived_from, old_path, new_path);
+                rename_derived_paths(&mut cluster.children, old_path, new_path);
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Comparison of generated code with the content of destination folder, used by `--diff` and `--verify`.
+//!
+//! Timestamps of generation in file headers are ignored, so only changes of register description
+//! file, options and svd2pac version are reported.
+use crate::DiffMode;
+use anyhow::{Context, Result};
+use lazy_regex::regex;
+use similar::TextDiff;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Collect paths of files in folder relative to `root`.
+/// Hidden files, `target` folder and `Cargo.lock` are not generated and are skipped.
+fn collect_files(root: &Path, folder: &Path, result: &mut BTreeSet<PathBuf>) -> Result<()> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_string_lossy();
+        if name.starts_with('.') || name == "target" || name == "Cargo.lock" {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(root, &path, result)?;
+        } else {
+            result.insert(path.strip_prefix(root)?.to_owned());
+        }
+    }
+    Ok(())
+}
+
+/// Content of file without generation timestamp, `None` if file is not text
+fn read_normalized(path: &Path) -> Result<Option<String>> {
+    let bytes = fs::read(path).with_context(|| format!("Unable to read {}", path.display()))?;
+    Ok(String::from_utf8(bytes).ok().map(|content| {
+        regex!(r"(Generated from SVD .*, with svd2pac \S+) on .*")
+            .replace_all(&content, "$1")
+            .into_owned()
+    }))
+}
+
+/// Report of differences between code generated in `new_folder` and content of `old_folder`.
+///
+/// Each added, removed or modified file is listed as `A`, `D` or `M` followed by its path.
+/// With [`DiffMode::Lines`] a unified diff of modified text files follows their path.
+/// Returns the number of changed files and the report.
+pub(crate) fn diff_folders(
+    new_folder: &Path,
+    old_folder: &Path,
+    mode: DiffMode,
+) -> Result<(usize, String)> {
+    let mut new_files = BTreeSet::new();
+    collect_files(new_folder, new_folder, &mut new_files)?;
+    let mut old_files = BTreeSet::new();
+    collect_files(old_folder, old_folder, &mut old_files)?;
+
+    let mut report = String::new();
+    let mut changes = 0;
+    for path in new_files.union(&old_files) {
+        let name = path.display();
+        if !old_files.contains(path) {
+            writeln!(report, "A {name}")?;
+        } else if !new_files.contains(path) {
+            writeln!(report, "D {name}")?;
+        } else {
+            let new_content = read_normalized(&new_folder.join(path))?;
+            let old_content = read_normalized(&old_folder.join(path))?;
+            let is_equal = match (&new_content, &old_content) {
+                (Some(new_content), Some(old_content)) => new_content == old_content,
+                _ => fs::read(new_folder.join(path))? == fs::read(old_folder.join(path))?,
+            };
+            if is_equal {
+                continue;
+            }
+            writeln!(report, "M {name}")?;
+            if let (DiffMode::Lines, Some(new_content), Some(old_content)) =
+                (mode, &new_content, &old_content)
+            {
+                let diff = TextDiff::from_lines(old_content, new_content);
+                write!(
+                    report,
+                    "{}",
+                    diff.unified_diff()
+                        .context_radius(3)
+                        .header(&format!("a/{name}"), &format!("b/{name}"))
+                )?;
+            }
+        }
+        changes += 1;
+    }
+    match changes {
+        0 => writeln!(report, "No changes in {}", old_folder.display())?,
+        changes => writeln!(
+            report,
+            "{changes} files changed in {}",
+            old_folder.display()
+        )?,
+    }
+    Ok((changes, report))
+}
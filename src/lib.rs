@@ -1,15 +1,27 @@
 #![doc = include_str!("../README.md")]
 
+mod commands;
+mod config;
+mod diff;
+mod rename_rules;
 mod rust_gen;
+mod strip_prefix;
+mod svd_diff;
 mod svd_util;
+mod transform;
+use crate::rename_rules::RenameRules;
 use crate::rust_gen::{generate_rust_package, GenPkgSettings};
-use clap::{Parser, ValueEnum};
+use crate::strip_prefix::StripPeripheralPrefix;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use env_logger::Env;
 use log::{error, info, warn};
 use serde::Serialize;
 use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+/// Device description model used by [`Transform`]
+pub use svd_parser::svd;
+pub use transform::Transform;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -20,6 +32,81 @@ pub enum SvdValidationLevel {
     Strict,
 }
 
+/// Format of register description file
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum InputFormat {
+    /// CMSIS System View Description file
+    Svd,
+    /// IP-XACT (IEEE 1685) component file. Each address block is converted to a peripheral.
+    IpXact,
+    /// Microchip/Atmel tools device file (ATDF). Each register group of a module instance is converted to a peripheral.
+    Atdf,
+    /// YAML device description using the same model as SVD files
+    Yaml,
+    /// JSON device description using the same model as SVD files
+    Json,
+}
+
+/// Action taken when a register is outside of the address blocks of its peripheral
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum AddressBlockCheck {
+    /// No check of register offsets
+    Disabled,
+    /// Log a warning for each register outside of address blocks
+    Warning,
+    /// Stop code generation if a register is outside of address blocks
+    Error,
+}
+
+/// Action taken when registers share the same address without being declared as alternate registers
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum AliasedRegisterCheck {
+    /// Generate accessors of all aliased registers with documentation noting the aliasing
+    Relaxed,
+    /// Same as relaxed but log a warning for each aliased register
+    Warning,
+    /// Stop code generation if registers are aliased
+    Error,
+}
+
+/// Details of differences printed by `--diff`
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum DiffMode {
+    /// List added, removed and modified files
+    Files,
+    /// List files and print changed lines of modified files
+    Lines,
+}
+
+/// Format of report of `diff` subcommand
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum ReportFormat {
+    /// A line for each change
+    Text,
+    /// JSON array of changes with kind, item, path, property, old and new value
+    Json,
+}
+
+/// Step of generation after which `--post-hook` command is executed
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum PostHookStage {
+    /// Before formatting of generated code with rustfmt
+    BeforeRustfmt,
+    /// After formatting of generated code with rustfmt
+    AfterRustfmt,
+}
+
+/// Inline attribute of generated register and bitfield accessors
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum InlineMode {
+    /// `#[inline(always)]` guarantees no call overhead for register access
+    Always,
+    /// `#[inline]` lets the compiler decide. Smaller code and faster debug builds.
+    Hint,
+    /// No inline attribute
+    Disabled,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Serialize)]
 pub enum Target {
     /// Only generic access to registers. No support for interrupt vector and NVIC priority bits.
@@ -28,30 +115,202 @@ pub enum Target {
     Aurix,
     /// Support for interrupt vector and NVIC priority bits. Compatible with existing cortex-m-rt crate
     CortexM,
+    /// Interrupt ID enumeration and interrupt vector table for VIC/GIC based Cortex-R devices. No NVIC support.
+    CortexR,
+    /// GIC interrupt IDs with SPI/PPI constants and per-cluster peripheral views for Cortex-A devices.
+    CortexA,
+    /// Interrupt vector table and interrupt levels for ESP32-class devices. Compatible with xtensa-lx-rt crate
+    Xtensa,
+    /// 16 bit registers by default and interrupt vector table compatible with msp430-rt crate
+    Msp430,
+    /// 8 bit registers by default, I/O space addresses and avr-device style interrupt names for 8 bit AVR devices
+    Avr,
+}
+
+/// Target selected in command line
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TargetSelection {
+    /// Target supported by svd2pac
+    Builtin(Target),
+    /// Target definition loaded at runtime from folder or TOML file
+    Custom(PathBuf),
+}
+
+/// Parse `--target` option. Value is either the name of a builtin target or `custom:<path>`.
+fn parse_target(value: &str) -> Result<TargetSelection, String> {
+    match value.strip_prefix("custom:") {
+        Some(path) => Ok(TargetSelection::Custom(PathBuf::from(path))),
+        None => <Target as ValueEnum>::from_str(value, true).map(TargetSelection::Builtin),
+    }
 }
 
 /// Generate peripheral access crate from SVD file
 #[derive(Parser, Debug)]
-#[command(author, version=env!("CARGO_PKG_VERSION"), about="Tool to generate peripheral access crate from SVD file", long_about = None)]
+#[command(author, version=env!("CARGO_PKG_VERSION"), about="Tool to generate peripheral access crate from SVD file", long_about = None, args_override_self = true)]
 pub struct Args {
+    /// TOML configuration file with command line options and settings that don't fit flags:
+    /// renames, peripheral filters and feature groups. Options given in command line override the ones of the file.
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub config: Option<PathBuf>,
     /// Disable formatting of generated code using rustfmt mainly for debugging
     #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
     pub disable_rust_fmt: bool,
-    /// Register description file
+    /// Shell command executed in destination folder after generation, e.g. to run a license scanner
+    /// or an additional formatter. Generation fails if the command fails.
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub post_hook: Option<String>,
+    /// Execute `--post-hook` command before or after formatting of generated code with rustfmt
+    #[arg(long,value_enum,default_value_t=PostHookStage::AfterRustfmt)]
+    pub post_hook_stage: PostHookStage,
+    /// Register description file. `http://` and `https://` URLs are downloaded and `-` reads from standard input.
     #[arg(value_parser=clap::value_parser!(PathBuf))]
     pub register_description_file_name: PathBuf,
+    /// Format of register description file. If not specified it is detected from file content.
+    #[arg(long,value_enum,default_value=None)]
+    pub input_format: Option<InputFormat>,
+    /// Name of device whose SVD is read when register description is a CMSIS-Pack (`.pack`, `.pdsc` or folder).
+    /// Can be omitted if the pack contains only one SVD file.
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub pack_device: Option<String>,
+    /// Expected SHA-256 checksum (hex) of register description file. Code generation fails if checksum doesn't match.
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub sha256: Option<String>,
+    /// svdtools compatible YAML patch file applied to register description before code generation
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub patch: Option<PathBuf>,
+    /// TOML file with regular expression based rename rules of peripherals, clusters, registers and bitfields
+    /// applied to register description before code generation, e.g. to strip vendor prefixes or fix typos.
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub rename_rules: Option<PathBuf>,
+    /// Remove redundant peripheral name prefix from names of registers and clusters, e.g. `UART_CR` of `UART`
+    /// peripheral is renamed to `CR`. Registers are not renamed if the new name collides with another register.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub strip_peripheral_prefix: bool,
+    /// Write normalized register description used for code generation to this SVD file.
+    /// Inherited register properties are resolved, arrays and `derivedFrom` are kept.
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub export_svd: Option<PathBuf>,
+    /// Generate code in a temporary folder and print the differences with the content of destination folder
+    /// without changing it. `files` lists added, removed and modified files, `lines` prints also the changed lines.
+    #[arg(long,value_enum,num_args=0..=1,require_equals=true,default_missing_value="files",default_value=None)]
+    pub diff: Option<DiffMode>,
+    /// Generate code in a temporary folder and fail if it differs from the content of destination folder,
+    /// e.g. to check that generated code in version control matches the register description file.
+    /// Destination folder is not changed.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub verify: bool,
     /// Destination folder of package
     #[arg(value_parser=clap::value_parser!(PathBuf))]
     pub destination_folder: PathBuf,
     //SVD validation level
     #[arg(long,value_enum,default_value_t=SvdValidationLevel::Weak)]
     pub svd_validation_level: SvdValidationLevel,
-    /// Architecture target of the PAC.
-    #[arg(long,value_enum,default_value_t=Target::Generic)]
-    pub target: Target,
+    /// Check that all registers are inside an address block of their peripheral
+    #[arg(long,value_enum,default_value_t=AddressBlockCheck::Warning)]
+    pub address_block_check: AddressBlockCheck,
+    /// Check registers that share the same address without declaring `alternateRegister` or `alternateGroup`,
+    /// e.g. registers whose meaning depends on peripheral mode
+    #[arg(long,value_enum,default_value_t=AliasedRegisterCheck::Warning)]
+    pub aliased_register_check: AliasedRegisterCheck,
+    /// Generate secure (`<PERIPHERAL>_S`) and non-secure (`<PERIPHERAL>_NS`) instances of all peripherals for ARMv8-M TrustZone devices.
+    /// The value is the address bit selecting the secure alias (usually 28).
+    /// Non-secure instances are generated only for peripherals inside non-secure SAU regions, if SAU regions are defined.
+    #[arg(long,value_parser=clap::value_parser!(u8).range(0..64),default_value=None)]
+    pub trustzone_alias_bit: Option<u8>,
+    /// Architecture target of the PAC: generic, aurix, cortex-m, cortex-r, cortex-a, xtensa, msp430, avr
+    /// or `custom:<path>` to load a target definition from a folder or TOML file.
+    #[arg(long,value_parser=parse_target,default_value="generic")]
+    pub target: TargetSelection,
+    /// SVD file describing core special function registers (CSFR) of Aurix devices. Each peripheral is converted
+    /// to a `csfr_<peripheral>` module accessed with `mfcr`/`mtcr` instructions.
+    /// If not specified the registers are read from `<aurixCSFR>` vendor extension of register description file.
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub aurix_csfr_file: Option<PathBuf>,
+    /// TOML or JSON file with additional variables of templates, available as `extra.<name>`,
+    /// e.g. vendor name or support URL used by templates of custom targets. JSON is used if extension is `.json`.
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub template_context: Option<PathBuf>,
     /// Enable the generation of a PAC with the tracing interface.
     #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
     pub tracing: bool,
+    /// Generate in `tests` folder of package a test file for each peripheral with unit tests of
+    /// code using the peripheral with mocked registers. Requires `--tracing`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub test_skeletons: bool,
+    /// Generate in `sim` folder of package a crate with one struct per peripheral storing the value
+    /// of its registers and overridable behavior on read and write, as starting point of a device simulator.
+    /// Requires `--tracing`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub simulator: bool,
+    /// Generate a cargo workspace with a crate for each peripheral module, or for each feature group of
+    /// configuration file, and a main crate that re-exports all peripherals enabled by features.
+    /// Not supported with `--tracing` and `--arbitrary`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub workspace: bool,
+    /// Generate the whole PAC in `lib.rs` without a file for each module
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub single_file: bool,
+    /// Write only the Rust modules in destination folder, without `Cargo.toml`, `build.rs` and license file,
+    /// to use them as module of an existing crate. The value is the path of the module in the crate, e.g. `crate::pac`.
+    /// `lib.rs` is generated as `mod.rs`.
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub modules_only: Option<String>,
+    /// Generate also Rust enums for enumerated bitfields in the `enums` module of each register.
+    /// Enums of incomplete enumerations are `#[non_exhaustive]`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub rust_enums: bool,
+    /// Generate `write_verify()` for read-write registers that reads back the register after write
+    /// and compares the bits of read-write bitfields without side effects on read or write.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub write_verify: bool,
+    /// Generate peripherals without absolute addresses.
+    /// Peripheral instances are not generated and peripherals are created with `from_ptr()` from a runtime base pointer.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub offset_only: bool,
+    /// Generate only once the structs of peripherals and clusters with identical layout of registers and bitfields.
+    /// Peripherals with the same layout of a previous one are handled as if they were derived from it.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub dedup_layouts: bool,
+    /// Inline attribute of generated register and bitfield accessors
+    #[arg(long,value_enum,default_value_t=InlineMode::Always)]
+    pub inline: InlineMode,
+    /// Generate `read_r()`, `write_w()` and `modify_rw()` that use distinct types for read and written values.
+    /// Only readable bitfields can be read from a read value and only writable bitfields can be set in a written value.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub split_read_write: bool,
+    /// Generate the `compat` module with svd2rust-style `read()`, `write(|w| ...)` and `modify(|r, w| ...)`
+    /// functions, available on registers with `compat()`. Intended for migration of drivers written for svd2rust.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub svd2rust_compat: bool,
+    /// Generate the `chiptool` module and `regs()` function of peripherals that returns registers with
+    /// chiptool-style access functions, e.g. `modify(|w| w.set_field(value))`. Intended for embassy HALs.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub chiptool_compat: bool,
+    /// Generate also builder-style `with_<field>(value)` setters of register values
+    /// in addition to `<field>().set(value)`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub with_setters: bool,
+    /// Make `read()` safe for registers whose register description declares no read side effects
+    /// (no `readAction` on register and bitfields). Writes and reads with side effects stay `unsafe`.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub safe_read: bool,
+    /// Generate `Peripherals::take()` that returns peripherals only once and `unsafe` `Peripherals::steal()`
+    /// like svd2rust. `take()` requires `critical-section` feature of generated package.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub peripherals_singleton: bool,
+    /// Generate a plain struct with values of all writable bitfields for each register and
+    /// `write_fields()` function of registers to write all bitfields at once.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub fields_struct: bool,
+    /// Generate a plain struct with values of all bitfields for each register, convertible from and into register value.
+    /// Intended for snapshots and comparisons of register content in host tests and configuration tools.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub unpacked: bool,
+    /// Generate implementations of `arbitrary::Arbitrary` (`arbitrary` feature) and `proptest::arbitrary::Arbitrary`
+    /// (`proptest` feature) for register values, with random values only in bits of bitfields.
+    /// A sample property test of register values is generated in `tests` folder of package.
+    #[arg(long,value_parser=clap::value_parser!(bool),default_value_t=false)]
+    pub arbitrary: bool,
     /// Define package name in toml. Default is name stored in register description file
     #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
     pub package_name: Option<String>,
@@ -60,8 +319,134 @@ pub struct Args {
     pub license_file: Option<PathBuf>,
 }
 
+/// Command line with subcommands. Without subcommand the command line is the one of `generate`.
+#[derive(Parser, Debug)]
+#[command(author, version=env!("CARGO_PKG_VERSION"), about="Tool to generate peripheral access crate from SVD file", long_about = None,
+    after_help = "Without subcommand the arguments are the ones of `generate`, e.g. `svd2pac <svd_file> <target directory>`")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Subcommands of svd2pac
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate peripheral access crate from register description file (default)
+    Generate(Box<Args>),
+    /// Check register description file without generating code
+    Validate(ValidateArgs),
+    /// Report added, removed and changed peripherals, registers, fields and interrupts of a new register description file
+    Diff(SvdDiffArgs),
+    /// Apply svdtools compatible YAML patch file and write the normalized SVD
+    Patch(PatchArgs),
+    /// Print a summary of device, peripherals and interrupts
+    Info(InfoArgs),
+}
+
+/// Options to read register description file used by subcommands
+#[derive(clap::Args, Debug)]
+pub struct InputArgs {
+    /// Format of register description file. If not specified it is detected from file content.
+    #[arg(long,value_enum,default_value=None)]
+    pub input_format: Option<InputFormat>,
+    /// Name of device whose SVD is read when register description is a CMSIS-Pack (`.pack`, `.pdsc` or folder).
+    /// Can be omitted if the pack contains only one SVD file.
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub pack_device: Option<String>,
+    //SVD validation level
+    #[arg(long,value_enum,default_value_t=SvdValidationLevel::Weak)]
+    pub svd_validation_level: SvdValidationLevel,
+}
+
+/// Arguments of `validate` subcommand. All checks fail on errors by default.
+#[derive(clap::Args, Debug)]
+pub struct ValidateArgs {
+    /// Register description file. `http://` and `https://` URLs are downloaded and `-` reads from standard input.
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub register_description_file_name: PathBuf,
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Expected SHA-256 checksum (hex) of register description file
+    #[arg(long,value_parser=clap::value_parser!(String),default_value=None)]
+    pub sha256: Option<String>,
+    /// svdtools compatible YAML patch file applied to register description before validation
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub patch: Option<PathBuf>,
+    /// Check that all registers are inside an address block of their peripheral
+    #[arg(long,value_enum,default_value_t=AddressBlockCheck::Error)]
+    pub address_block_check: AddressBlockCheck,
+    /// Check registers that share the same address without declaring `alternateRegister` or `alternateGroup`
+    #[arg(long,value_enum,default_value_t=AliasedRegisterCheck::Error)]
+    pub aliased_register_check: AliasedRegisterCheck,
+}
+
+/// Arguments of `diff` subcommand
+#[derive(clap::Args, Debug)]
+pub struct SvdDiffArgs {
+    /// Old register description file
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub old_file_name: PathBuf,
+    /// New register description file
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub new_file_name: PathBuf,
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// svdtools compatible YAML patch file applied to both register descriptions before comparison
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub patch: Option<PathBuf>,
+    /// Format of report of changes
+    #[arg(long,value_enum,default_value_t=ReportFormat::Text)]
+    pub format: ReportFormat,
+}
+
+/// Arguments of `patch` subcommand
+#[derive(clap::Args, Debug)]
+pub struct PatchArgs {
+    /// Register description file. `http://` and `https://` URLs are downloaded and `-` reads from standard input.
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub register_description_file_name: PathBuf,
+    /// svdtools compatible YAML patch file
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub patch_file_name: PathBuf,
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Patched SVD file. If not specified the patched SVD is printed to standard output.
+    #[arg(long,short,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments of `info` subcommand
+#[derive(clap::Args, Debug)]
+pub struct InfoArgs {
+    /// Register description file. `http://` and `https://` URLs are downloaded and `-` reads from standard input.
+    #[arg(value_parser=clap::value_parser!(PathBuf))]
+    pub register_description_file_name: PathBuf,
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// svdtools compatible YAML patch file applied to register description
+    #[arg(long,value_parser=clap::value_parser!(PathBuf),default_value=None)]
+    pub patch: Option<PathBuf>,
+}
+
+/// Check if first command line argument selects a subcommand other than `generate` or asks for help of subcommands.
+/// A register description file named like a subcommand in current directory is not a subcommand.
+fn is_subcommand(arg: &str) -> bool {
+    if Path::new(arg).exists() {
+        return false;
+    }
+    matches!(arg, "help" | "-h" | "--help")
+        || (arg != "generate"
+            && Cli::command()
+                .get_subcommands()
+                .any(|subcommand| subcommand.get_name() == arg))
+}
+
 /// Main function that parses command line parameters after parsing it invoking [`main`]
 ///
+/// The first argument can be a subcommand (`generate`, `validate`, `diff`, `patch` or `info`).
+/// Without subcommand the arguments are the ones of `generate`.
+/// Options of the configuration file given with `--config` are added to command line parameters of `generate`.
+///
 /// # Arguments
 ///
 /// * `args` - List of command line arguments. The first argument should be the path of executable, but it is ignored by this function.
@@ -78,11 +463,53 @@ where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
+    let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    match args.get(1).and_then(|arg| arg.to_str()) {
+        Some(arg) if is_subcommand(arg) => return run_command(Cli::parse_from(args).command),
+        Some("generate") if !Path::new("generate").exists() => {
+            args.remove(1);
+        }
+        _ => (),
+    }
+    let args = config::expand_arguments(args)
+        .unwrap_or_else(|err| panic!("Failed to read configuration file: {err:#}"));
     self::main(Args::parse_from(args));
 }
 
 /// Convert SVD file to PAC
 pub fn main(args: Args) {
+    main_with_transforms(args, Vec::new());
+}
+
+/// Execute subcommand
+pub fn run_command(command: Command) {
+    let (result, action) = match command {
+        Command::Generate(args) => return main(*args),
+        Command::Validate(args) => {
+            init_logger();
+            (commands::validate(&args), "validate")
+        }
+        Command::Diff(args) => {
+            init_logger();
+            (commands::diff(&args), "compare")
+        }
+        Command::Patch(args) => {
+            init_logger();
+            (commands::patch(&args), "patch")
+        }
+        Command::Info(args) => {
+            init_logger();
+            (commands::info(&args), "read")
+        }
+    };
+    if let Err(err) = result {
+        error!("{:#}", err);
+        panic!("Failed to {action} register description file");
+    }
+}
+
+/// Initialize logger configured by `SVD2PAC_LOG_LEVEL` and `SVD2PAC_LOG_STYLE` environment variables
+fn init_logger() {
     // Use
     let env = Env::default()
         .filter_or("SVD2PAC_LOG_LEVEL", "info")
@@ -93,29 +520,139 @@ pub fn main(args: Args) {
     if let Err(error) = env_logger::try_init_from_env(env) {
         warn!("{}", error);
     }
+}
+
+/// Transformations of configuration file, then rename rules, stripping of peripheral prefixes
+/// and user defined transformations
+fn build_transforms(
+    config: &config::Config,
+    rename_rules: Option<&Path>,
+    strip_peripheral_prefix: bool,
+    transforms: Vec<Box<dyn Transform>>,
+) -> anyhow::Result<Vec<Box<dyn Transform>>> {
+    let mut result = config.transforms()?;
+    if let Some(path) = rename_rules {
+        result.push(Box::new(RenameRules::load(path)?));
+    }
+    if strip_peripheral_prefix {
+        result.push(Box::new(StripPeripheralPrefix));
+    }
+    result.extend(transforms);
+    Ok(result)
+}
+
+/// Convert SVD file to PAC applying user defined transformations to the device description before code generation
+///
+/// # Arguments
+///
+/// * `args` - Command line arguments
+/// * `transforms` - Transformations applied in order after parsing and patching of register description file
+///
+/// # Examples
+///
+/// ```ignore
+/// use clap::Parser;
+/// let args = svd2pac::Args::parse_from(["", "./test_svd/simple.xml", "./generated_code"]);
+/// let remove_uart = |device: &mut svd2pac::svd::Device| -> anyhow::Result<()> {
+///     device.peripherals.retain(|peripheral| peripheral.name != "UART");
+///     Ok(())
+/// };
+/// svd2pac::main_with_transforms(args, vec![Box::new(remove_uart)]);
+/// ```
+pub fn main_with_transforms(args: Args, transforms: Vec<Box<dyn Transform>>) {
+    init_logger();
 
     info!(
         "Reading register description file {}",
         args.register_description_file_name.to_str().unwrap()
     );
     let destination_folder = args.destination_folder;
+    // With `--diff` or `--verify` code is generated in a temporary folder and compared with destination folder
+    let diff_folder = (args.diff.is_some() || args.verify).then(|| {
+        tempfile::tempdir().unwrap_or_else(|err| panic!("Failed to create temporary folder: {err}"))
+    });
+    let generation_folder = diff_folder
+        .as_ref()
+        .map_or(destination_folder.as_path(), |folder| folder.path());
 
-    if !destination_folder.exists() {
-        info!("Create folder {}", &destination_folder.to_str().unwrap());
-        if let Err(err) = fs::create_dir_all(&destination_folder) {
+    if !generation_folder.exists() {
+        info!("Create folder {}", &generation_folder.to_str().unwrap());
+        if let Err(err) = fs::create_dir_all(generation_folder) {
             error!("Failed to create destination folder: {}", err);
             panic!("Failed to create folder")
         };
     }
 
+    // Settings of configuration file that don't fit command line options
+    let config = match args.config.as_deref().map(config::Config::load).transpose() {
+        Ok(config) => config.unwrap_or_default(),
+        Err(err) => {
+            error!("{:#}", err);
+            panic!("Failed to read configuration file")
+        }
+    };
+    // Transformations of configuration file and rename rules are applied before user defined transformations
+    let transforms = match build_transforms(
+        &config,
+        args.rename_rules.as_deref(),
+        args.strip_peripheral_prefix,
+        transforms,
+    ) {
+        Ok(transforms) => transforms,
+        Err(err) => {
+            error!("{:#}", err);
+            panic!("Invalid configuration or rename rules file")
+        }
+    };
+
+    let (target, target_definition) = match args.target {
+        TargetSelection::Builtin(target) => (target, None),
+        // Builtin target used by custom target is defined in target definition
+        TargetSelection::Custom(path) => (Target::Generic, Some(path)),
+    };
+
     if let Err(err) = generate_rust_package(
         &args.register_description_file_name,
-        &destination_folder,
+        generation_folder,
         GenPkgSettings {
             run_rustfmt: !args.disable_rust_fmt,
+            post_hook: args.post_hook,
+            post_hook_stage: args.post_hook_stage,
+            input_format: args.input_format,
+            pack_device: args.pack_device,
+            sha256: args.sha256,
+            patch: args.patch,
+            export_svd: args.export_svd,
+            transforms,
             svd_validation_level: args.svd_validation_level,
-            target: args.target,
+            address_block_check: args.address_block_check,
+            aliased_register_check: args.aliased_register_check,
+            trustzone_alias_bit: args.trustzone_alias_bit,
+            target,
+            target_definition,
+            template_context: args.template_context,
+            aurix_csfr_file: args.aurix_csfr_file,
             tracing: args.tracing,
+            test_skeletons: args.test_skeletons,
+            simulator: args.simulator,
+            feature_groups: config.feature_groups(),
+            workspace: args.workspace,
+            single_file: args.single_file,
+            modules_only: args.modules_only,
+            rust_enums: args.rust_enums,
+            write_verify: args.write_verify,
+            offset_only: args.offset_only,
+            dedup_layouts: args.dedup_layouts,
+            inline: args.inline,
+            split_read_write: args.split_read_write,
+            svd2rust_compat: args.svd2rust_compat,
+            chiptool_compat: args.chiptool_compat,
+            with_setters: args.with_setters,
+            safe_read: args.safe_read,
+            peripherals_singleton: args.peripherals_singleton,
+            fields_struct: args.fields_struct,
+            unpacked: args.unpacked,
+            arbitrary: args.arbitrary,
             package_name: args.package_name,
             license_file: args.license_file,
             svd2pac_version: VERSION.to_owned(),
@@ -124,4 +661,26 @@ pub fn main(args: Args) {
         error!("Failed to generate code with err {}", err);
         panic!("Failed to generate code");
     }
+
+    if let Some(diff_folder) = diff_folder {
+        let mode = args.diff.unwrap_or(DiffMode::Files);
+        let (changes, report) =
+            match diff::diff_folders(diff_folder.path(), &destination_folder, mode) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("{:#}", err);
+                    panic!("Failed to compare generated code with destination folder")
+                }
+            };
+        if args.diff.is_some() || changes > 0 {
+            print!("{report}");
+        }
+        if args.verify && changes > 0 {
+            error!(
+                "Generated code differs from {}",
+                destination_folder.display()
+            );
+            panic!("Verification of generated code failed");
+        }
+    }
 }
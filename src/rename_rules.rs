@@ -0,0 +1,174 @@
+//! Regular expression based rename rules of peripherals, clusters, registers and bitfields.
+//!
+//! Rules are read from a TOML file and applied in order to the names of all matching items,
+//! e.g. to strip vendor prefixes or fix typos without editing the register description file.
+//!
+//! # Example
+//!
+//! ```toml
+//! # Strip vendor prefix of registers of UART peripherals
+//! [[rules]]
+//! items = "registers"
+//! peripheral = "UART*"
+//! pattern = "^ACME_UART_"
+//! replacement = ""
+//!
+//! # Fix typo in names of all items
+//! [[rules]]
+//! pattern = "REGSITER"
+//! replacement = "REGISTER"
+//! ```
+use crate::rust_gen::glob_to_regex;
+use crate::{svd, Transform};
+use anyhow::{anyhow, Context, Result};
+use lazy_regex::Regex;
+use log::debug;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Kind of items renamed by a rule
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Items {
+    #[default]
+    All,
+    Peripherals,
+    Clusters,
+    Registers,
+    Fields,
+}
+
+/// Rule as written in rules file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDef {
+    #[serde(default)]
+    items: Items,
+    /// Glob of peripherals whose items are renamed, all peripherals if not specified
+    peripheral: Option<String>,
+    /// Regular expression matched against item names
+    pattern: String,
+    /// Replacement of matches, `$1` or `${name}` refer to capture groups
+    replacement: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleDef>,
+}
+
+struct Rule {
+    items: Items,
+    peripheral: Option<Regex>,
+    pattern: Regex,
+    replacement: String,
+}
+
+impl Rule {
+    /// Apply rule to name of item of kind `items`
+    fn rename(&self, items: Items, name: &mut String) -> Result<()> {
+        if self.items != Items::All && self.items != items {
+            return Ok(());
+        }
+        let new_name = self
+            .pattern
+            .replace_all(name.as_str(), self.replacement.as_str());
+        if new_name != *name {
+            if new_name.is_empty() {
+                return Err(anyhow!(
+                    "Rename rule {} removes the whole name of {name}",
+                    self.pattern
+                ));
+            }
+            debug!("Rename {name} to {new_name}");
+            *name = new_name.into_owned();
+        }
+        Ok(())
+    }
+}
+
+/// Rename rules read from file, applied as transformation of device description
+pub(crate) struct RenameRules(Vec<Rule>);
+
+impl RenameRules {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read rename rules file {}", path.display()))?;
+        let file: RulesFile = toml::from_str(&content)
+            .with_context(|| format!("Invalid rename rules file {}", path.display()))?;
+        let rules = file
+            .rules
+            .into_iter()
+            .map(|rule| -> Result<Rule> {
+                Ok(Rule {
+                    items: rule.items,
+                    peripheral: rule.peripheral.as_deref().map(glob_to_regex).transpose()?,
+                    pattern: Regex::new(&rule.pattern)
+                        .with_context(|| format!("Invalid rename pattern {}", rule.pattern))?,
+                    replacement: rule.replacement,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self(rules))
+    }
+}
+
+impl Transform for RenameRules {
+    fn name(&self) -> &str {
+        "rename rules"
+    }
+
+    fn apply(&self, device: &mut svd::Device) -> Result<()> {
+        for rule in &self.0 {
+            // New names of peripherals, used to update `derivedFrom` of other peripherals
+            let mut renamed_peripherals = HashMap::new();
+            for peripheral in device.peripherals.iter_mut() {
+                if rule
+                    .peripheral
+                    .as_ref()
+                    .is_some_and(|p| !p.is_match(&peripheral.name))
+                {
+                    continue;
+                }
+                if let Some(registers) = peripheral.registers.as_mut() {
+                    rename_registers(rule, registers)?;
+                }
+                let old_name = peripheral.name.clone();
+                rule.rename(Items::Peripherals, &mut peripheral.name)?;
+                if peripheral.name != old_name {
+                    renamed_peripherals.insert(old_name, peripheral.name.clone());
+                }
+            }
+            for peripheral in device.peripherals.iter_mut() {
+                if let Some(derived_from) = peripheral.derived_from.as_mut() {
+                    if let Some(new_name) = renamed_peripherals.get(derived_from) {
+                        derived_from.clone_from(new_name);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn rename_registers(rule: &Rule, registers: &mut [svd::RegisterCluster]) -> Result<()> {
+    for register in registers.iter_mut() {
+        match register {
+            svd::RegisterCluster::Register(register) => {
+                for field in register.fields.iter_mut().flatten() {
+                    rule.rename(Items::Fields, &mut field.name)?;
+                }
+                rule.rename(Items::Registers, &mut register.name)?;
+            }
+            svd::RegisterCluster::Cluster(cluster) => {
+                rename_registers(rule, &mut cluster.children)?;
+                rule.rename(Items::Clusters, &mut cluster.name)?;
+            }
+        }
+    }
+    Ok(())
+}
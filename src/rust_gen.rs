@@ -1,17 +1,38 @@
+mod atdf2svd;
+mod aurix_core;
+mod aurix_src;
+mod cmsis_pack;
+mod custom_target;
+mod ipxact2svd;
 mod ir;
+mod mock;
+mod patch;
+mod raw_layout;
+mod serde2svd;
+mod sim;
+mod svd2xml;
 mod util;
+mod workspace;
 mod xml2ir;
 use std::{
+    cell::RefCell,
     fs::create_dir_all,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
+pub(crate) use self::patch::glob_to_regex;
 use self::util::ToSanitizedSymbol;
-use crate::{SvdValidationLevel, Target};
+use crate::{
+    AddressBlockCheck, AliasedRegisterCheck, InlineMode, InputFormat, PostHookStage,
+    SvdValidationLevel, Target, Transform,
+};
 use anyhow::{anyhow, Context, Result};
 use lazy_regex::regex;
+use linked_hash_map::LinkedHashMap;
 use log::{error, info, warn};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt::Write;
 use std::fs;
@@ -176,17 +197,48 @@ fn filter_to_func_id(value: &Value, _args: &HashMap<String, Value>) -> tera::Res
 }
 
 /// Sanitize a string so it can be used in doc attribute
+///
+/// Whitespaces are normalized, HTML formatting tags are removed and characters
+/// that have a special meaning in markdown or HTML are escaped.
+/// The `%s` placeholder of array descriptions is replaced by the optional `index` argument,
+/// used when each element of array has its own item. Otherwise it is replaced by `` `index` ``,
+/// the argument of the accessor of array elements.
 fn filter_svd_description_to_doc(
     value: &Value,
-    _args: &HashMap<String, Value>,
+    args: &HashMap<String, Value>,
 ) -> tera::Result<Value> {
+    let index = match args.get("index") {
+        Some(index) => index
+            .as_str()
+            .map(str::to_owned)
+            .unwrap_or_else(|| index.to_string()),
+        None => "`index`".to_owned(),
+    };
     if let Value::String(doc_string) = value {
+        let line_break_reg_ex = regex!(r"(?i)<br\s*/?>");
+        let html_tag_reg_ex =
+            regex!(r"(?i)</?(b|i|u|p|em|strong|sub|sup|code|ul|ol|li|div|span)(\s[^<>]*)?>");
+        let whitespace_reg_ex = regex!(r"[ \t]+");
+        let doc_string = doc_string.replace("\\n", "\n");
+        let doc_string = line_break_reg_ex.replace_all(&doc_string, "\n");
+        let doc_string = html_tag_reg_ex.replace_all(&doc_string, "");
+        let doc_string = doc_string
+            .lines()
+            .map(|line| whitespace_reg_ex.replace_all(line.trim(), " "))
+            .collect::<Vec<_>>()
+            .join("\n");
         Ok(Value::String(
             doc_string
+                .trim()
                 .replace('[', r"\[")
                 .replace(']', r"\]")
+                .replace('*', r"\*")
+                .replace('`', r"\`")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
                 .escape_debug()
-                .to_string(),
+                .to_string()
+                .replace("%s", &index),
         ))
     } else {
         Err(tera::Error::msg(
@@ -238,6 +290,24 @@ fn get_tera_instance() -> anyhow::Result<Tera> {
             "reg_name.tera",
             include_str!("../templates/rust/reg_name.tera"),
         ),
+        ("mock.tera", include_str!("../templates/rust/mock.tera")),
+        ("hook.tera", include_str!("../templates/rust/hook.tera")),
+        (
+            "register_values_test.tera",
+            include_str!("../templates/rust/register_values_test.tera"),
+        ),
+        (
+            "mock_test.tera",
+            include_str!("../templates/rust/mock_test.tera"),
+        ),
+        (
+            "sim_lib.tera",
+            include_str!("../templates/rust/sim_lib.tera"),
+        ),
+        (
+            "sim_cargo_toml.tera",
+            include_str!("../templates/rust/sim_cargo_toml.tera"),
+        ),
         (
             "peri_mod.tera",
             include_str!("../templates/rust/peri_mod.tera"),
@@ -246,7 +316,16 @@ fn get_tera_instance() -> anyhow::Result<Tera> {
             "aurix_core.tera",
             include_str!("../templates/rust/aurix_core.tera"),
         ),
+        (
+            "aurix_src.tera",
+            include_str!("../templates/rust/aurix_src.tera"),
+        ),
         ("common.tera", include_str!("../templates/rust/common.tera")),
+        ("compat.tera", include_str!("../templates/rust/compat.tera")),
+        (
+            "chiptool.tera",
+            include_str!("../templates/rust/chiptool.tera"),
+        ),
         ("macros.tera", include_str!("../templates/rust/macros.tera")),
         (
             "build_cortex.tera",
@@ -268,6 +347,159 @@ fn get_xml_string(path: &Path, xml: &mut String) -> Result<()> {
     Ok(())
 }
 
+/// Check if register description file is an URL that shall be downloaded
+fn is_url(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|path| path.starts_with("http://") || path.starts_with("https://"))
+}
+
+#[cfg(feature = "download")]
+fn download_xml_string(url: &str, xml: &mut String) -> Result<()> {
+    info!("Download register description file from {}", url);
+    ureq::get(url)
+        .call()
+        .with_context(|| format!("Cannot download register description file from {url}"))?
+        .into_reader()
+        .read_to_string(xml)
+        .context("Cannot read downloaded register description file")?;
+    Ok(())
+}
+
+#[cfg(not(feature = "download"))]
+fn download_xml_string(url: &str, _xml: &mut String) -> Result<()> {
+    Err(anyhow!(
+        "Cannot download register description file from {url}. svd2pac is built without feature `download`"
+    ))
+}
+
+/// Compare SHA-256 checksum of register description with expected one
+fn verify_sha256(xml: &str, expected: &str) -> Result<()> {
+    use sha2::{Digest, Sha256};
+    let checksum = Sha256::digest(xml.as_bytes())
+        .iter()
+        .fold(String::new(), |mut output, byte| {
+            let _ = write!(output, "{byte:02x}");
+            output
+        });
+    if checksum != expected.trim().to_lowercase() {
+        return Err(anyhow!(
+            "SHA-256 checksum of register description file is {checksum}, expected {expected}"
+        ));
+    }
+    info!("SHA-256 checksum of register description file verified");
+    Ok(())
+}
+
+/// Device as SVD with inherited register properties resolved, arrays and `derivedFrom` are kept
+pub(crate) fn normalized_svd(svd_device: &svd_parser::svd::Device) -> String {
+    let mut normalized_device = svd_device.clone();
+    svd_parser::expand_properties(&mut normalized_device);
+    svd2xml::encode(&normalized_device)
+}
+
+/// Write device used for code generation as SVD file
+fn export_normalized_svd(svd_device: &svd_parser::svd::Device, path: &Path) -> Result<()> {
+    info!(
+        "Export normalized register description to {}",
+        path.display()
+    );
+    fs::write(path, normalized_svd(svd_device)).context("Cannot write normalized SVD file")?;
+    Ok(())
+}
+
+/// Settings to read and patch register description file
+pub struct ReadSettings {
+    /// Detected from extension and content of file if not specified
+    pub input_format: Option<InputFormat>,
+    pub pack_device: Option<String>,
+    pub sha256: Option<String>,
+    pub patch: Option<PathBuf>,
+    pub svd_validation_level: SvdValidationLevel,
+}
+
+/// Read register description file from file, URL, CMSIS-Pack or standard input and apply patch file
+///
+/// # Result
+///
+/// Device, format of register description file, detected if not specified by `settings`,
+/// and content of register description file
+pub(crate) fn read_register_description(
+    xml_path: &Path,
+    settings: &ReadSettings,
+) -> Result<(svd_parser::svd::Device, InputFormat, String)> {
+    let ReadSettings {
+        input_format,
+        ref pack_device,
+        ref sha256,
+        ref patch,
+        svd_validation_level,
+    } = *settings;
+    let xml = &mut String::new();
+    if xml_path == Path::new("-") {
+        info!("Read register description from standard input");
+        std::io::stdin()
+            .read_to_string(xml)
+            .context("Cannot read register description from standard input")?;
+    } else if is_url(xml_path) {
+        download_xml_string(&xml_path.to_string_lossy(), xml)?;
+    } else if cmsis_pack::is_cmsis_pack(xml_path) {
+        cmsis_pack::read_svd(xml_path, pack_device.as_deref(), xml)?;
+    } else {
+        get_xml_string(xml_path, xml)?;
+    }
+    if let Some(expected_checksum) = sha256 {
+        verify_sha256(xml, expected_checksum)?;
+    }
+    let input_format = input_format.unwrap_or_else(|| detect_input_format(xml_path, xml));
+    info!("Register description file format: {:?}", input_format);
+    let mut svd_device = match input_format {
+        InputFormat::Svd => xml2ir::parse_xml(xml, svd_validation_level)?,
+        InputFormat::IpXact => ipxact2svd::parse_ipxact(xml, svd_validation_level)?,
+        InputFormat::Atdf => atdf2svd::parse_atdf(xml, svd_validation_level)?,
+        InputFormat::Yaml => serde2svd::parse_yaml(xml)?,
+        InputFormat::Json => serde2svd::parse_json(xml)?,
+    };
+    if let Some(patch_path) = patch {
+        info!("Apply patch file {}", patch_path.display());
+        patch::apply_patch_file(&mut svd_device, patch_path)?;
+    }
+    Ok((svd_device, input_format, std::mem::take(xml)))
+}
+
+/// Check conversion of device to intermediate representation used by code generation,
+/// e.g. registers outside of address blocks or aliased registers
+pub(crate) fn check_device(
+    svd_device: &svd_parser::svd::Device,
+    address_block_check: AddressBlockCheck,
+    aliased_register_check: AliasedRegisterCheck,
+) -> Result<()> {
+    xml2ir::svd_device2ir(
+        svd_device,
+        &None,
+        address_block_check,
+        aliased_register_check,
+        None,
+        false,
+    )?;
+    Ok(())
+}
+
+/// Detect format of register description file from its extension or content
+fn detect_input_format(path: &Path, xml: &str) -> InputFormat {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yaml") | Some("yml") => return InputFormat::Yaml,
+        Some("json") => return InputFormat::Json,
+        _ => (),
+    }
+    if regex!(r"<\s*(ipxact|spirit):component\b").is_match(xml) {
+        InputFormat::IpXact
+    } else if regex!(r"<\s*avr-tools-device-file\b").is_match(xml) {
+        InputFormat::Atdf
+    } else {
+        InputFormat::Svd
+    }
+}
+
 // check if vendor extension is present in the svd
 fn check_for_vendor_extension(path: &Path) -> Result<bool> {
     let file = File::open(path)?;
@@ -312,11 +544,84 @@ pub fn get_aurix_csfr_svd(path: &Path, svd_string: &mut String) -> Result<()> {
     Ok(())
 }
 
+/// Options of code generation available in templates and macros as `options`
+#[derive(Serialize, Debug)]
+struct TemplateOptions {
+    /// Generate Rust enums for enumerated bitfields
+    rust_enums: bool,
+    /// Generate bit-band accessors for peripherals in bit-band regions
+    bit_band: bool,
+    /// Generate `write_verify()` for registers
+    write_verify: bool,
+    /// Don't generate absolute addresses. Peripherals are created from a runtime base pointer.
+    offset_only: bool,
+    /// Inline attribute of accessors. Empty if no attribute is generated.
+    inline: &'static str,
+    /// Generate distinct types for read and written register values
+    split_read_write: bool,
+    /// Generate svd2rust-style register access in `compat` module
+    svd2rust_compat: bool,
+    /// Generate chiptool-style register access in `chiptool` module
+    chiptool_compat: bool,
+    /// Generate `with_<field>()` setters of register values
+    with_setters: bool,
+    /// Make `read()` safe for registers without read side effects
+    safe_read: bool,
+    /// `Peripherals::take()` returns peripherals only once
+    peripherals_singleton: bool,
+    /// Generate plain structs with values of writable bitfields of registers
+    fields_struct: bool,
+    /// Generate plain structs with values of all bitfields of registers
+    unpacked: bool,
+    /// Generate `Arbitrary` implementations of register values and a sample property test
+    arbitrary: bool,
+}
+
 pub struct GenPkgSettings {
     pub run_rustfmt: bool,
+    /// Shell command executed in destination folder after generation
+    pub post_hook: Option<String>,
+    pub post_hook_stage: PostHookStage,
+    pub input_format: Option<InputFormat>,
+    pub pack_device: Option<String>,
+    pub sha256: Option<String>,
+    pub patch: Option<PathBuf>,
+    pub export_svd: Option<PathBuf>,
+    pub transforms: Vec<Box<dyn Transform>>,
     pub svd_validation_level: SvdValidationLevel,
+    pub address_block_check: AddressBlockCheck,
+    pub aliased_register_check: AliasedRegisterCheck,
+    pub trustzone_alias_bit: Option<u8>,
     pub target: Target,
+    pub target_definition: Option<PathBuf>,
+    /// File of additional variables of templates
+    pub template_context: Option<PathBuf>,
+    pub aurix_csfr_file: Option<PathBuf>,
     pub tracing: bool,
+    pub test_skeletons: bool,
+    pub simulator: bool,
+    /// Cargo features enabling groups of peripherals
+    pub feature_groups: BTreeMap<String, Vec<String>>,
+    /// Generate a workspace with a crate for each peripheral module or feature group
+    pub workspace: bool,
+    /// Generate all modules of a crate in `lib.rs`
+    pub single_file: bool,
+    /// Generate only modules in destination folder to be used as this module of an existing crate
+    pub modules_only: Option<String>,
+    pub rust_enums: bool,
+    pub write_verify: bool,
+    pub offset_only: bool,
+    pub dedup_layouts: bool,
+    pub inline: InlineMode,
+    pub split_read_write: bool,
+    pub svd2rust_compat: bool,
+    pub chiptool_compat: bool,
+    pub with_setters: bool,
+    pub safe_read: bool,
+    pub peripherals_singleton: bool,
+    pub fields_struct: bool,
+    pub unpacked: bool,
+    pub arbitrary: bool,
     pub package_name: Option<String>,
     pub license_file: Option<PathBuf>,
     pub svd2pac_version: String,
@@ -370,6 +675,26 @@ fn generate_tracing_module(
     execute_template(tera, "tracing.tera", context, &lib_path)
         .context("Failed generation of tracing.rs")?;
 
+    // mock module, register file used by `mock` feature instead of tracing handlers
+    let mut mock_context = context.clone();
+    mock_context.insert("mock_registers", &mock::get_mock_registers(&ir.device));
+    execute_template(
+        tera,
+        "mock.tera",
+        &mock_context,
+        &destination_folder.join("src/mock.rs"),
+    )
+    .context("Failed generation of mock.rs")?;
+
+    // hook module, write hook usable in `no_std` builds
+    execute_template(
+        tera,
+        "hook.tera",
+        context,
+        &destination_folder.join("src/hook.rs"),
+    )
+    .context("Failed generation of hook.rs")?;
+
     let svd2pac_version = context.get("svd2pac_version").unwrap().as_str();
     let now = context.get("now").unwrap().as_str();
     // reg_name module
@@ -416,6 +741,249 @@ fn generate_tracing_module(
     Ok(())
 }
 
+/// Execute command of `--post-hook` with the shell of the platform in destination folder
+fn run_post_hook(command: &str, destination_folder: &Path) -> anyhow::Result<()> {
+    info!("Executing post-generation hook {command}");
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .current_dir(destination_folder)
+        .status()
+        .with_context(|| format!("Unable to execute post-generation hook {command}"))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "Post-generation hook {command} failed with {status}"
+        ));
+    }
+    Ok(())
+}
+
+/// Time of generation available as `now` to templates of custom targets.
+/// Builtin templates don't use it, so generated code depends only on inputs.
+/// `SOURCE_DATE_EPOCH` environment variable replaces current time if defined.
+fn generation_time() -> anyhow::Result<String> {
+    let time = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => {
+            let seconds: i64 = epoch
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid SOURCE_DATE_EPOCH {epoch}"))?;
+            chrono::DateTime::from_timestamp(seconds, 0)
+                .with_context(|| format!("SOURCE_DATE_EPOCH {epoch} out of range"))?
+        }
+        Err(_) => chrono::Utc::now(),
+    };
+    Ok(time.to_rfc2822())
+}
+
+/// Convert names of peripherals of feature groups to names of features of peripherals
+fn get_feature_groups(
+    device: &ir::Device,
+    feature_groups: &BTreeMap<String, Vec<String>>,
+) -> anyhow::Result<BTreeMap<String, Vec<String>>> {
+    let mut result = BTreeMap::new();
+    for (group, peripherals) in feature_groups {
+        let is_peripheral_feature = device
+            .peripheral_mod
+            .values()
+            .any(|peri| peri.borrow().name.to_sanitized_mod_ident() == *group);
+        if is_peripheral_feature || group == "all" {
+            return Err(anyhow!(
+                "Feature group {group} has the same name of a generated feature"
+            ));
+        }
+        let features = peripherals
+            .iter()
+            .map(|name| {
+                device
+                    .peripheral_mod
+                    .values()
+                    .find(|peri| peri.borrow().name == *name)
+                    .map(|peri| peri.borrow().name.to_sanitized_mod_ident())
+                    .ok_or_else(|| anyhow!("No peripheral {name} in feature group {group}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        result.insert(group.clone(), features);
+    }
+    Ok(result)
+}
+
+/// Generate a test file for each peripheral in `tests` folder of package
+fn generate_test_skeletons(
+    tera: &Tera,
+    ir: &ir::IR,
+    destination_folder: &Path,
+    context: &tera::Context,
+) -> anyhow::Result<()> {
+    for (peri_name, peri) in &ir.device.peripheral_mod {
+        let mut context = context.clone();
+        context.insert("peri_name", peri_name);
+        context.insert("peri", peri);
+        let file_name = format!(
+            "tests/{}.rs",
+            peri.borrow().name.clone().to_sanitized_mod_ident()
+        );
+        execute_template(
+            tera,
+            "mock_test.tera",
+            &context,
+            &destination_folder.join(&file_name),
+        )
+        .with_context(|| format!("Failed generation of {file_name}"))?;
+    }
+    Ok(())
+}
+
+/// Generate crate with simulated peripherals of device
+fn generate_simulator(
+    tera: &Tera,
+    ir: &ir::IR,
+    destination_folder: &Path,
+    context: &tera::Context,
+) -> anyhow::Result<()> {
+    let mut context = context.clone();
+    context.insert("sim_peripherals", &sim::get_sim_peripherals(&ir.device));
+    execute_template(
+        tera,
+        "sim_lib.tera",
+        &context,
+        &destination_folder.join("sim/src/lib.rs"),
+    )
+    .context("Failed generation of sim/src/lib.rs")?;
+    execute_template(
+        tera,
+        "sim_cargo_toml.tera",
+        &context,
+        &destination_folder.join("sim/Cargo.toml"),
+    )
+    .context("Failed generation of sim/Cargo.toml")?;
+    Ok(())
+}
+
+/// Generate crates of workspace with peripheral modules and their own common module
+fn generate_workspace_crates(
+    tera: &Tera,
+    ir: &ir::IR,
+    workspace_crates: &[ir::WorkspaceCrate],
+    destination_folder: &Path,
+    context: &tera::Context,
+    options: &TemplateOptions,
+) -> anyhow::Result<()> {
+    for member in workspace_crates {
+        let member_folder = destination_folder.join(&member.path);
+        let mut member_ir = ir.clone();
+        member_ir.device.peripheral_mod = ir
+            .device
+            .peripheral_mod
+            .iter()
+            .filter(|(_, peri)| member.modules.contains(&peri.borrow().module_id))
+            .map(|(name, peri)| (name.clone(), peri.clone()))
+            .collect();
+        // Crate of workspace has only peripherals, device level items are in main crate
+        let mut context = context.clone();
+        context.insert("ir", &member_ir);
+        context.insert("package_name", &member.package);
+        context.insert("crate_name", &member.ident);
+        context.insert("workspace_crates", &Vec::<ir::WorkspaceCrate>::new());
+        context.insert("workspace_member", &true);
+        context.insert("feature_groups", &BTreeMap::<String, Vec<String>>::new());
+        context.insert("vector_table", &false);
+        context.insert("interrupt_enum", &false);
+        generate_peripheral_module(
+            tera,
+            &member_ir,
+            "peri_mod.tera",
+            &member_folder,
+            &context,
+            options,
+        )?;
+        generate_common_module(tera, &member_ir, &member_folder, &context)?;
+        if options.svd2rust_compat {
+            execute_template(
+                tera,
+                "compat.tera",
+                &context,
+                &member_folder.join("src/compat.rs"),
+            )
+            .context("Failed generation of compat.rs")?;
+        }
+        if options.chiptool_compat {
+            execute_template(
+                tera,
+                "chiptool.tera",
+                &context,
+                &member_folder.join("src/chiptool.rs"),
+            )
+            .context("Failed generation of chiptool.rs")?;
+        }
+        generate_lib_rs_module(tera, &member_folder, &context)?;
+        generate_cargo_toml(tera, &member_folder, &context)?;
+        fs::write(member_folder.join("LICENSE.txt"), &ir.license_text)?;
+    }
+    Ok(())
+}
+
+/// Replace declarations of modules in `lib.rs` with the content of their files and remove the files
+fn inline_modules(src_folder: &Path) -> anyhow::Result<()> {
+    let lib_path = src_folder.join("lib.rs");
+    let lib = fs::read_to_string(&lib_path).context("Unable to read lib.rs")?;
+    let mut result = String::with_capacity(lib.len());
+    for line in lib.lines() {
+        let module = regex!(r"^\s*pub mod (\w+);\s*$")
+            .captures(line)
+            .map(|captures| captures[1].to_owned())
+            .filter(|name| src_folder.join(format!("{name}.rs")).is_file());
+        match module {
+            Some(name) => {
+                let module_path = src_folder.join(format!("{name}.rs"));
+                let content = fs::read_to_string(&module_path)
+                    .with_context(|| format!("Unable to read {}", module_path.display()))?;
+                writeln!(result, "pub mod {name} {{\n{content}\n}}")?;
+                fs::remove_file(&module_path)?;
+            }
+            None => writeln!(result, "{line}")?,
+        }
+    }
+    fs::write(&lib_path, result).context("Unable to write lib.rs")?;
+    Ok(())
+}
+
+/// Move content of `src` folder to destination folder, `lib.rs` becomes `mod.rs`.
+/// Paths relative to crate root in generated code are changed to paths relative to `module_path`.
+fn move_modules(destination_folder: &Path, module_path: &str) -> anyhow::Result<()> {
+    let src_folder = destination_folder.join("src");
+    let module_prefix = format!("{module_path}::");
+    for entry in fs::read_dir(&src_folder)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap();
+        let target_path = if file_name == "lib.rs" {
+            destination_folder.join("mod.rs")
+        } else {
+            destination_folder.join(file_name)
+        };
+        if path.extension().is_some_and(|extension| extension == "rs") {
+            let code = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read {}", path.display()))?;
+            let code = regex!(r"\bcrate::").replace_all(&code, module_prefix.as_str());
+            fs::write(&target_path, code.as_bytes())
+                .with_context(|| format!("Unable to write {}", target_path.display()))?;
+            fs::remove_file(&path)?;
+        } else {
+            fs::rename(&path, &target_path)?;
+        }
+    }
+    fs::remove_dir(&src_folder)?;
+    Ok(())
+}
+
 fn generate_common_module(
     tera: &Tera,
     _ir: &ir::IR,
@@ -428,13 +996,75 @@ fn generate_common_module(
     Ok(())
 }
 
+/// Collect registers, if `readable_only` only the ones that can be read without side effects.
+/// Cluster and register arrays are unrolled.
+fn collect_dump_registers(
+    registers: &LinkedHashMap<String, Rc<RefCell<ir::Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<ir::Cluster>>>,
+    name_prefix: &str,
+    path_prefix: &[ir::DumpPathStep],
+    readable_only: bool,
+    result: &mut Vec<ir::DumpRegister>,
+) {
+    for register in registers.values() {
+        let register = register.borrow();
+        if readable_only
+            && (register.access == ir::RegisterAccess::W || register.has_read_side_effects)
+        {
+            continue;
+        }
+        for index in 0..register.dim {
+            let (name, index) = if register.dim == 1 {
+                (format!("{name_prefix}{}", register.name), None)
+            } else {
+                (
+                    format!("{name_prefix}{}[{index}]", register.name),
+                    Some(index),
+                )
+            };
+            let mut path = path_prefix.to_vec();
+            path.push(ir::DumpPathStep {
+                name: register.name.clone(),
+                index,
+            });
+            result.push(ir::DumpRegister { name, path });
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            let (name_prefix, index) = if cluster.dim == 1 {
+                (format!("{name_prefix}{}.", cluster.name), None)
+            } else {
+                (
+                    format!("{name_prefix}{}[{index}].", cluster.name),
+                    Some(index),
+                )
+            };
+            let mut path = path_prefix.to_vec();
+            path.push(ir::DumpPathStep {
+                name: cluster.name.clone(),
+                index,
+            });
+            collect_dump_registers(
+                &cluster.registers,
+                &cluster.clusters,
+                &name_prefix,
+                &path,
+                readable_only,
+                result,
+            );
+        }
+    }
+}
+
 fn generate_peripheral_module(
     tera: &Tera,
     ir: &ir::IR,
     template_name: &str,
     destination_folder: &Path,
-    svd2pac_version: &str,
-    now: &str,
+    package_context: &tera::Context,
+    options: &TemplateOptions,
 ) -> anyhow::Result<()> {
     // Generate one module for each peripheral
     for (_, peri) in &ir.device.peripheral_mod {
@@ -444,11 +1074,45 @@ fn generate_peripheral_module(
             continue;
         }
         let module_name = borrowed_peri.module_id.clone();
+        // Absolute addresses of registers are known only if register types are used by a single peripheral instance
+        let is_shared_module = ir.device.peripheral_mod.values().any(|other| {
+            let other = other.borrow();
+            other.module_id == module_name && other.name != borrowed_peri.name
+        });
+        let base_address = match borrowed_peri.base_addr.as_slice() {
+            [base_address] if !is_shared_module && !options.offset_only => Some(*base_address),
+            _ => None,
+        };
+        let mut dump_registers = Vec::new();
+        collect_dump_registers(
+            &borrowed_peri.registers,
+            &borrowed_peri.clusters,
+            "",
+            &[],
+            true,
+            &mut dump_registers,
+        );
+        let mut decode_registers = Vec::new();
+        collect_dump_registers(
+            &borrowed_peri.registers,
+            &borrowed_peri.clusters,
+            "",
+            &[],
+            false,
+            &mut decode_registers,
+        );
         let mut context = tera::Context::new();
         context.insert("peri", peri);
+        context.insert("base_address", &base_address);
+        context.insert("dump_registers", &dump_registers);
+        context.insert("decode_registers", &decode_registers);
+        context.insert("raw_layout", &raw_layout::get_raw_layout(&borrowed_peri));
         context.insert("ir", &ir);
-        context.insert("svd2pac_version", svd2pac_version);
-        context.insert("now", now);
+        // Variables of package used also in header of modules and by custom templates
+        for key in ["svd2pac_version", "now", "extra"] {
+            context.insert(key, package_context.get(key).unwrap());
+        }
+        context.insert("options", options);
         execute_template(
             tera,
             template_name,
@@ -466,9 +1130,43 @@ fn generate_aurix_core_ir(
 ) -> anyhow::Result<Option<ir::IR>> {
     let GenPkgSettings {
         run_rustfmt: _,
+        post_hook: _,
+        post_hook_stage: _,
+        input_format: _,
+        pack_device: _,
+        sha256: _,
+        patch: _,
+        export_svd: _,
+        transforms: _,
         svd_validation_level,
+        address_block_check,
+        aliased_register_check,
+        trustzone_alias_bit: _,
         target: _,
+        target_definition: _,
+        template_context: _,
+        aurix_csfr_file,
         tracing: _,
+        test_skeletons: _,
+        simulator: _,
+        feature_groups: _,
+        workspace: _,
+        single_file: _,
+        modules_only: _,
+        rust_enums: _,
+        write_verify: _,
+        offset_only: _,
+        dedup_layouts: _,
+        inline: _,
+        split_read_write: _,
+        svd2rust_compat: _,
+        chiptool_compat: _,
+        with_setters: _,
+        safe_read: _,
+        peripherals_singleton: _,
+        fields_struct: _,
+        unpacked: _,
+        arbitrary: _,
         package_name: _,
         license_file,
         svd2pac_version: _,
@@ -479,17 +1177,33 @@ fn generate_aurix_core_ir(
     let custom_license_text = license_file.as_ref().map(|path| {
         fs::read_to_string(path).unwrap_or_else(|_| panic!("Unable to read license file {path:?}"))
     });
-    // If target is aurix, create csfr
-    let result = check_for_vendor_extension(xml_path)?;
-    if result {
-        let svd_csfr_xml = &mut String::with_capacity(500);
+    // Core registers are read from separate SVD file if specified
+    // otherwise from vendor extension of register description file
+    let svd_csfr_xml = &mut String::with_capacity(500);
+    if let Some(csfr_path) = aurix_csfr_file {
+        info!(
+            "Reading csfr register description file {}",
+            csfr_path.display()
+        );
+        get_xml_string(csfr_path, svd_csfr_xml)?;
+    } else if check_for_vendor_extension(xml_path)? {
         get_aurix_csfr_svd(xml_path, svd_csfr_xml)?;
+    }
+    if !svd_csfr_xml.is_empty() {
         let mut svd_device = xml2ir::parse_xml(svd_csfr_xml, *svd_validation_level)?;
         // Rename peripherals
         for peri in svd_device.peripherals.iter_mut() {
             peri.name = "csfr_".to_string() + &peri.name
         }
-        let ir_csfr = xml2ir::svd_device2ir(&svd_device, &custom_license_text)?;
+        let ir_csfr = xml2ir::svd_device2ir(
+            &svd_device,
+            &custom_license_text,
+            *address_block_check,
+            *aliased_register_check,
+            None,
+            // Core register modules are generated with a dedicated template that doesn't share structs
+            false,
+        )?;
         Ok(Some(ir_csfr))
     } else {
         Ok(None)
@@ -503,77 +1217,352 @@ pub(crate) fn generate_rust_package(
 ) -> anyhow::Result<()> {
     let GenPkgSettings {
         run_rustfmt,
+        ref post_hook,
+        post_hook_stage,
+        input_format,
+        ref pack_device,
+        ref sha256,
+        ref patch,
+        ref export_svd,
+        ref transforms,
         svd_validation_level,
+        address_block_check,
+        aliased_register_check,
+        trustzone_alias_bit,
         target,
+        ref target_definition,
+        ref template_context,
+        ref aurix_csfr_file,
         tracing,
+        test_skeletons,
+        simulator,
+        ref feature_groups,
+        workspace,
+        single_file,
+        ref modules_only,
+        rust_enums,
+        write_verify,
+        offset_only,
+        dedup_layouts,
+        inline,
+        split_read_write,
+        svd2rust_compat,
+        chiptool_compat,
+        with_setters,
+        safe_read,
+        peripherals_singleton,
+        fields_struct,
+        unpacked,
+        arbitrary,
         ref package_name,
         ref license_file,
         ref svd2pac_version,
     } = settings;
 
     info!("Start generating rust code");
+    // Custom target uses templates and settings of its base target if not overridden
+    let target_definition = target_definition
+        .as_deref()
+        .map(custom_target::load_target_definition)
+        .transpose()?;
+    let target = target_definition
+        .as_ref()
+        .map_or(target, |definition| definition.base);
+    let extra_context = template_context
+        .as_deref()
+        .map(custom_target::load_template_context)
+        .transpose()?
+        .unwrap_or_default();
     // Read license file if specified
     let custom_license_text = license_file.as_ref().map(|path| {
         fs::read_to_string(path).unwrap_or_else(|_| panic!("Unable to read license file {path:?}"))
     });
 
-    let xml = &mut String::new();
-    get_xml_string(xml_path, xml)?;
-    let svd_device = xml2ir::parse_xml(xml, svd_validation_level)?;
-    let ir = xml2ir::svd_device2ir(&svd_device, &custom_license_text)?;
+    let (mut svd_device, input_format, xml) = read_register_description(
+        xml_path,
+        &ReadSettings {
+            input_format,
+            pack_device: pack_device.clone(),
+            sha256: sha256.clone(),
+            patch: patch.clone(),
+            svd_validation_level,
+        },
+    )?;
+    // Registers of 8 and 16 bit devices have the size of data bus if not specified.
+    // Registers inherit it together with the other properties after transformations.
+    if svd_device.default_register_properties.size.is_none() {
+        let default_register_size = match target {
+            Target::Msp430 => Some(16),
+            Target::Avr => Some(8),
+            _ => None,
+        };
+        svd_device.default_register_properties.size = target_definition
+            .as_ref()
+            .and_then(|definition| definition.default_register_size)
+            .or(default_register_size);
+    }
+    for transform in transforms {
+        info!("Apply transformation {}", transform.name());
+        transform
+            .apply(&mut svd_device)
+            .with_context(|| format!("Transformation {} failed", transform.name()))?;
+    }
+    // Registers added by patch or transformations inherit size, access and reset value
+    svd_parser::expand_properties(&mut svd_device);
+    if let Some(export_path) = export_svd {
+        export_normalized_svd(&svd_device, export_path)?;
+    }
+    let trustzone = match trustzone_alias_bit {
+        Some(alias_bit) => Some(xml2ir::TrustZone {
+            alias_bit,
+            // SAU regions can be defined only in SVD files
+            non_secure_regions: match input_format {
+                InputFormat::Svd => xml2ir::get_non_secure_sau_regions(&xml)?,
+                _ => None,
+            },
+        }),
+        None => None,
+    };
+    let ir = xml2ir::svd_device2ir(
+        &svd_device,
+        &custom_license_text,
+        address_block_check,
+        aliased_register_check,
+        trustzone.as_ref(),
+        dedup_layouts,
+    )?;
     //Precompile templates
     let mut tera = get_tera_instance()?;
     precompile_tera(&mut tera);
+    if let Some(ref definition) = target_definition {
+        custom_target::add_templates(&mut tera, definition)?;
+    }
+
+    if target == Target::CortexM && ir.nvic_prio_bits.is_none() {
+        warn!("No cpu element with nvicPrioBits in register description. NVIC_PRIO_BITS required by RTIC is not generated");
+    }
+
+    if test_skeletons && (!tracing || offset_only) {
+        return Err(anyhow!(
+            "Test skeletons require tracing interface and absolute addresses of peripherals"
+        ));
+    }
+
+    if simulator && (!tracing || offset_only) {
+        return Err(anyhow!(
+            "Simulator requires tracing interface and absolute addresses of peripherals"
+        ));
+    }
+
+    if let Some(module_path) = modules_only {
+        if !regex!(r"^crate(::\w+)*$").is_match(module_path) {
+            return Err(anyhow!(
+                "Invalid module path {module_path}, expected a path like crate::pac"
+            ));
+        }
+        if workspace || test_skeletons || simulator {
+            return Err(anyhow!(
+                "Modules only output doesn't support workspace, test skeletons and simulator"
+            ));
+        }
+    }
+
+    if workspace && (tracing || arbitrary) {
+        return Err(anyhow!(
+            "Workspace output doesn't support tracing interface and arbitrary register values"
+        ));
+    }
+
+    let feature_groups = get_feature_groups(&ir.device, feature_groups)?;
 
     let package_name: String = match package_name {
         None => ir.device.name.clone().to_lowercase(),
         Some(ref package_name) => package_name.clone(),
     };
 
-    let now = chrono::Utc::now().to_rfc2822();
+    let workspace_crates = if workspace {
+        workspace::get_workspace_crates(&ir.device, &package_name, &feature_groups, offset_only)?
+    } else {
+        Vec::new()
+    };
+
+    let now = generation_time()?;
 
     let mut context = tera::Context::new();
     context.insert("ir", &ir);
     context.insert("target", &target);
+    if let Some(ref definition) = target_definition {
+        context.insert("custom_target", &definition.name);
+        context.insert("custom", &definition.context);
+    }
+    context.insert("extra", &extra_context);
+    context.insert(
+        "vector_table",
+        &matches!(
+            target,
+            Target::CortexM | Target::CortexR | Target::Xtensa | Target::Msp430
+        ),
+    );
+    context.insert(
+        "interrupt_enum",
+        &matches!(
+            target,
+            Target::CortexM
+                | Target::CortexR
+                | Target::CortexA
+                | Target::Xtensa
+                | Target::Msp430
+                | Target::Avr
+        ),
+    );
     context.insert("tracing", &tracing);
+    context.insert("test_skeletons", &test_skeletons);
+    context.insert("feature_groups", &feature_groups);
+    context.insert("workspace_crates", &workspace_crates);
+    context.insert("workspace_member", &false);
+    context.insert("modules_only", &modules_only.is_some());
+    let options = TemplateOptions {
+        rust_enums,
+        // Bit-band alias addresses are derived from the addresses in register description file
+        bit_band: target == Target::CortexM && !offset_only,
+        write_verify,
+        offset_only,
+        inline: match inline {
+            InlineMode::Always => "#[inline(always)]",
+            InlineMode::Hint => "#[inline]",
+            InlineMode::Disabled => "",
+        },
+        split_read_write,
+        svd2rust_compat,
+        chiptool_compat,
+        with_setters,
+        safe_read,
+        peripherals_singleton,
+        fields_struct,
+        unpacked,
+        arbitrary,
+    };
+    context.insert("options", &options);
     context.insert("package_name", &package_name);
+    context.insert("crate_name", &package_name.replace('-', "_"));
     context.insert("description", "Description tests");
     context.insert("svd2pac_version", svd2pac_version);
     context.insert("now", &now);
 
-    // Generate peripheral modules
-    generate_peripheral_module(
-        &tera,
-        &ir,
-        "peri_mod.tera",
-        destination_folder,
-        svd2pac_version,
-        &now,
-    )?;
+    // Generate peripheral modules, in crates of workspace if requested
+    if workspace {
+        generate_workspace_crates(
+            &tera,
+            &ir,
+            &workspace_crates,
+            destination_folder,
+            &context,
+            &options,
+        )?;
+    } else {
+        generate_peripheral_module(
+            &tera,
+            &ir,
+            "peri_mod.tera",
+            destination_folder,
+            &context,
+            &options,
+        )?;
+    }
 
     //Generate common module
     generate_common_module(&tera, &ir, destination_folder, &context)?;
 
+    // Generate svd2rust compatibility module
+    if svd2rust_compat {
+        execute_template(
+            &tera,
+            "compat.tera",
+            &context,
+            &destination_folder.join("src/compat.rs"),
+        )
+        .context("Failed generation of compat.rs")?;
+    }
+
+    // Generate chiptool compatibility module
+    if chiptool_compat {
+        execute_template(
+            &tera,
+            "chiptool.tera",
+            &context,
+            &destination_folder.join("src/chiptool.rs"),
+        )
+        .context("Failed generation of chiptool.rs")?;
+    }
+
     // Generate tracing related modules
     if tracing {
         generate_tracing_module(&tera, &ir, destination_folder, &context)?;
     }
 
+    // Generate sample property test of register values
+    if arbitrary && modules_only.is_none() {
+        execute_template(
+            &tera,
+            "register_values_test.tera",
+            &context,
+            &destination_folder.join("tests/register_values.rs"),
+        )
+        .context("Failed generation of tests/register_values.rs")?;
+    }
+
+    // Generate unit tests with mocked registers
+    if test_skeletons {
+        generate_test_skeletons(&tera, &ir, destination_folder, &context)?;
+    }
+
+    // Generate simulator crate in `sim` folder of package
+    if simulator {
+        generate_simulator(&tera, &ir, destination_folder, &context)?;
+    }
+
     // If target is aurix, create csfr modules
-    if settings.target == Target::Aurix {
-        let ir_csfr = generate_aurix_core_ir(xml_path, &settings)?;
+    // Vendor extensions of Aurix are read only from plain SVD files
+    if aurix_csfr_file.is_some() && target != Target::Aurix {
+        warn!("Core register description file is used only by aurix target. File ignored");
+    }
+    let ir_csfr = if target == Target::Aurix
+        && (aurix_csfr_file.is_some()
+            || (input_format == InputFormat::Svd
+                && xml_path.is_file()
+                && !cmsis_pack::is_cmsis_pack(xml_path)))
+    {
+        generate_aurix_core_ir(xml_path, &settings)?
+    } else {
+        None
+    };
+
+    // Generate cpu peripheral modules
+    if let Some(ref ir) = ir_csfr {
+        generate_peripheral_module(
+            &tera,
+            ir,
+            "aurix_core.tera",
+            destination_folder,
+            &context,
+            &options,
+        )?;
+        context.insert("ir_csfr", &ir_csfr);
+    }
 
-        // Generate cpu peripheral modules
-        if let Some(ref ir) = ir_csfr {
-            generate_peripheral_module(
+    // If target is aurix, create core local views and service request helpers
+    // Both refer to absolute addresses of peripherals
+    if target == Target::Aurix && !offset_only {
+        context.insert("aurix_cores", &aurix_core::get_aurix_cores(&ir, &ir_csfr));
+        if let Some(service_request_table) = aurix_src::get_service_request_table(&ir.device) {
+            context.insert("service_request_table", &service_request_table);
+            execute_template(
                 &tera,
-                ir,
-                "aurix_core.tera",
-                destination_folder,
-                svd2pac_version,
-                &now,
-            )?;
-            context.insert("ir_csfr", &ir_csfr);
+                "aurix_src.tera",
+                &context,
+                &destination_folder.join("src/service_request.rs"),
+            )
+            .context("Failed generation of service_request.rs")?;
         }
     }
 
@@ -581,10 +1570,29 @@ pub(crate) fn generate_rust_package(
     generate_lib_rs_module(&tera, destination_folder, &context)?;
 
     //generate Cargo.toml
-    generate_cargo_toml(&tera, destination_folder, &context)?;
+    if modules_only.is_none() {
+        generate_cargo_toml(&tera, destination_folder, &context)?;
+    }
 
-    // If cortex-m add build.rs and device.x
-    if settings.target == Target::CortexM {
+    // Generate additional files of custom target, only modules if package is not generated
+    for file in target_definition
+        .iter()
+        .flat_map(|definition| definition.files.iter())
+        .filter(|file| modules_only.is_none() || file.output.starts_with("src"))
+    {
+        execute_template(
+            &tera,
+            &file.template,
+            &context,
+            &destination_folder.join(&file.output),
+        )
+        .with_context(|| format!("Failed generation of {}", file.output.display()))?;
+    }
+
+    // If cortex-m, cortex-r or msp430 add build.rs and device.x
+    if matches!(target, Target::CortexM | Target::CortexR | Target::Msp430)
+        && modules_only.is_none()
+    {
         execute_template(
             &tera,
             "device_x.tera",
@@ -601,7 +1609,32 @@ pub(crate) fn generate_rust_package(
         .context("Failed to generate build.rs file")?;
     }
 
-    let lib_path = destination_folder.join("src/lib.rs");
+    // Move content of module files in lib.rs of each crate
+    if single_file {
+        inline_modules(&destination_folder.join("src"))?;
+        for member in &workspace_crates {
+            inline_modules(&destination_folder.join(&member.path).join("src"))?;
+        }
+    }
+
+    // Move modules from src folder to destination folder
+    let lib_path = match modules_only {
+        Some(module_path) => {
+            move_modules(destination_folder, module_path)?;
+            destination_folder.join("mod.rs")
+        }
+        None => destination_folder.join("src/lib.rs"),
+    };
+    // Add license file
+    if modules_only.is_none() {
+        fs::write(destination_folder.join("LICENSE.txt"), &ir.license_text)?;
+    }
+
+    if let Some(command) = post_hook {
+        if post_hook_stage == PostHookStage::BeforeRustfmt {
+            run_post_hook(command, destination_folder)?;
+        }
+    }
     // Run rustfmt on generated code
     if run_rustfmt {
         // Check rustfmt is available
@@ -610,6 +1643,16 @@ pub(crate) fn generate_rust_package(
             Ok(_) => {
                 info!("Formatting code with rustfmt");
                 Command::new("rustfmt").arg(lib_path).status()?;
+                if simulator {
+                    Command::new("rustfmt")
+                        .arg(destination_folder.join("sim/src/lib.rs"))
+                        .status()?;
+                }
+                for member in &workspace_crates {
+                    Command::new("rustfmt")
+                        .arg(destination_folder.join(&member.path).join("src/lib.rs"))
+                        .status()?;
+                }
             }
             // if not able to run with --help proceed just with a warning. Generated code is anyway valid.
             Err(_) => {
@@ -617,8 +1660,11 @@ pub(crate) fn generate_rust_package(
             }
         }
     };
-    // Add license file
-    fs::write(destination_folder.join("LICENSE.txt"), ir.license_text)?;
+    if let Some(command) = post_hook {
+        if post_hook_stage == PostHookStage::AfterRustfmt {
+            run_post_hook(command, destination_folder)?;
+        }
+    }
 
     info!("Completed code generation");
     Ok(())
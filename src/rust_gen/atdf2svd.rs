@@ -0,0 +1,295 @@
+//! Convert Microchip/Atmel ATDF device description to svd device.
+//!
+//! Each register group referenced by a module instance is converted to a peripheral.
+//! Value groups referenced by bitfields are converted to enumerated values.
+use super::xml2ir::get_validate_level;
+use crate::SvdValidationLevel;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use roxmltree::Node;
+use svd_parser::svd::{self, ValidateLevel};
+
+/// Get all children elements with given name.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Get first child element with given name.
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal number
+fn parse_number(value: &str) -> Result<u64> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    }
+    .context(format!("Invalid number {value}"))
+}
+
+/// Get number stored in attribute of node
+fn attribute_number(node: Node, name: &str) -> Result<Option<u64>> {
+    node.attribute(name).map(parse_number).transpose()
+}
+
+fn parse_access(node: Node) -> Option<svd::Access> {
+    match node.attribute("rw")? {
+        "R" => Some(svd::Access::ReadOnly),
+        "W" => Some(svd::Access::WriteOnly),
+        "RW" => Some(svd::Access::ReadWrite),
+        "" => None,
+        access => {
+            warn!("Unsupported access {access}. Access inherited from parent");
+            None
+        }
+    }
+}
+
+fn parse_value_group(
+    module: Node,
+    value_group_name: &str,
+    level: ValidateLevel,
+) -> Result<Vec<svd::EnumeratedValues>> {
+    let Some(value_group) = children(module, "value-group")
+        .find(|group| group.attribute("name") == Some(value_group_name))
+    else {
+        warn!("Value group {value_group_name} not found. Enumerated values skipped");
+        return Ok(Vec::new());
+    };
+    let mut values = Vec::new();
+    for value in children(value_group, "value") {
+        values.push(
+            svd::EnumeratedValue::builder()
+                .name(
+                    value
+                        .attribute("name")
+                        .context("value without name")?
+                        .to_owned(),
+                )
+                .description(value.attribute("caption").map(str::to_owned))
+                .value(attribute_number(value, "value")?)
+                .build(level)?,
+        );
+    }
+    Ok(vec![svd::EnumeratedValues::builder()
+        .values(values)
+        .build(level)?])
+}
+
+fn parse_bitfield(module: Node, node: Node, level: ValidateLevel) -> Result<Option<svd::Field>> {
+    let name = node.attribute("name").context("bitfield without name")?;
+    let mask =
+        attribute_number(node, "mask")?.with_context(|| format!("bitfield {name} without mask"))?;
+    if mask == 0 {
+        return Err(anyhow!("Bitfield {name} has empty mask"));
+    }
+    let offset = mask.trailing_zeros();
+    let width = mask.count_ones();
+    // Mask of all 64 bits cannot be computed by shifting
+    let contiguous_mask = 1u64.checked_shl(width).map_or(u64::MAX, |bit| bit - 1);
+    if mask >> offset != contiguous_mask {
+        warn!("Bitfield {name} has non contiguous mask 0x{mask:x}. Bitfield skipped");
+        return Ok(None);
+    }
+    let enumerated_values = match node.attribute("values") {
+        Some(value_group_name) => parse_value_group(module, value_group_name, level)?,
+        None => Vec::new(),
+    };
+    let field = svd::FieldInfo::builder()
+        .name(name.to_owned())
+        .description(node.attribute("caption").map(str::to_owned))
+        .bit_range(svd::BitRange::from_offset_width(offset, width))
+        .access(parse_access(node))
+        .enumerated_values(enumerated_values)
+        .build(level)?;
+    Ok(Some(svd::Field::Single(field)))
+}
+
+fn parse_register(module: Node, node: Node, level: ValidateLevel) -> Result<svd::RegisterCluster> {
+    let name = node.attribute("name").context("register without name")?;
+    debug!("Parsing ATDF register: {}", name);
+    let mut fields = Vec::new();
+    for bitfield in children(node, "bitfield") {
+        if let Some(field) = parse_bitfield(module, bitfield, level)? {
+            fields.push(field);
+        }
+    }
+    // Size of registers in ATDF is in bytes
+    let size = attribute_number(node, "size")?.unwrap_or(1) * 8;
+    let register = svd::RegisterInfo::builder()
+        .description(node.attribute("caption").map(str::to_owned))
+        .address_offset(
+            attribute_number(node, "offset")?
+                .with_context(|| format!("register {name} without offset"))? as u32,
+        )
+        .size(Some(size as u32))
+        .access(parse_access(node).or(Some(svd::Access::ReadWrite)))
+        .reset_value(Some(attribute_number(node, "initval")?.unwrap_or_default()))
+        .fields(if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        });
+    // Registers with count attribute are arrays of consecutive registers
+    let register = match attribute_number(node, "count")? {
+        None | Some(1) => svd::Register::Single(register.name(name.to_owned()).build(level)?),
+        Some(count) => {
+            let dim_element = svd::DimElement::builder()
+                .dim(count as u32)
+                .dim_increment(size as u32 / 8)
+                .build(level)?;
+            svd::Register::Array(
+                register.name(format!("{name}[%s]")).build(level)?,
+                dim_element,
+            )
+        }
+    };
+    Ok(svd::RegisterCluster::Register(register))
+}
+
+/// Create a peripheral from a register group of module
+fn parse_register_group(
+    module: Node,
+    register_group: Node,
+    name: &str,
+    base_address: u64,
+    interrupts: Vec<svd::Interrupt>,
+    level: ValidateLevel,
+) -> Result<svd::Peripheral> {
+    debug!("Parsing ATDF register group: {}", name);
+    let registers = children(register_group, "register")
+        .map(|register| parse_register(module, register, level))
+        .collect::<Result<Vec<_>>>()?;
+    let peripheral = svd::PeripheralInfo::builder()
+        .name(name.to_owned())
+        .description(
+            register_group
+                .attribute("caption")
+                .or_else(|| module.attribute("caption"))
+                .map(str::to_owned),
+        )
+        .base_address(base_address)
+        .interrupt(Some(interrupts))
+        .registers(Some(registers))
+        .build(level)?;
+    Ok(svd::Peripheral::Single(peripheral))
+}
+
+/// Parse ATDF xml and transform it to device description of svd_rs module
+///
+/// # Arguments
+///
+/// * `xml` content of ATDF file
+///
+/// # Result
+///
+/// Device containing all module instances as peripherals
+pub(super) fn parse_atdf(
+    xml: &str,
+    svd_validation_level: SvdValidationLevel,
+) -> Result<svd::Device> {
+    let level = get_validate_level(svd_validation_level);
+    let document = roxmltree::Document::parse(xml).context("Invalid ATDF xml")?;
+    let root = document.root_element();
+    if root.tag_name().name() != "avr-tools-device-file" {
+        return Err(anyhow!("ATDF root element shall be avr-tools-device-file"));
+    }
+    let device = child(root, "devices")
+        .and_then(|devices| child(devices, "device"))
+        .context("No device defined in ATDF file")?;
+    let device_name = device.attribute("name").context("device without name")?;
+    let modules = child(root, "modules").context("No modules defined in ATDF file")?;
+
+    // Interrupts are assigned to the peripheral of module instance
+    let mut interrupts: Vec<(Option<&str>, svd::Interrupt)> = Vec::new();
+    for interrupt in child(device, "interrupts")
+        .into_iter()
+        .flat_map(|interrupts| children(interrupts, "interrupt"))
+    {
+        let interrupt_name = interrupt
+            .attribute("name")
+            .context("interrupt without name")?;
+        let svd_interrupt = svd::Interrupt::builder()
+            .name(interrupt_name.to_owned())
+            .description(interrupt.attribute("caption").map(str::to_owned))
+            .value(
+                attribute_number(interrupt, "index")?
+                    .with_context(|| format!("interrupt {interrupt_name} without index"))?
+                    as u32,
+            )
+            .build(level)?;
+        interrupts.push((interrupt.attribute("module-instance"), svd_interrupt));
+    }
+
+    let mut peripherals = Vec::new();
+    for module in child(device, "peripherals")
+        .into_iter()
+        .flat_map(|peripherals| children(peripherals, "module"))
+    {
+        let module_name = module.attribute("name").context("module without name")?;
+        let Some(module_definition) =
+            children(modules, "module").find(|m| m.attribute("name") == Some(module_name))
+        else {
+            warn!("Definition of module {module_name} not found. Module skipped");
+            continue;
+        };
+        for instance in children(module, "instance") {
+            let instance_name = instance
+                .attribute("name")
+                .context("instance without name")?;
+            let group_refs: Vec<_> = children(instance, "register-group").collect();
+            for (index, group_ref) in group_refs.iter().enumerate() {
+                let group_name = group_ref
+                    .attribute("name-in-module")
+                    .or_else(|| group_ref.attribute("name"))
+                    .context("register-group without name")?;
+                let Some(register_group) = children(module_definition, "register-group")
+                    .find(|g| g.attribute("name") == Some(group_name))
+                else {
+                    warn!("Register group {group_name} of module {module_name} not found");
+                    continue;
+                };
+                // Use instance name if only one register group is defined
+                let name = if group_refs.len() == 1 {
+                    instance_name.to_owned()
+                } else {
+                    format!("{instance_name}_{group_name}")
+                };
+                // Interrupts are assigned only to first register group of instance
+                let instance_interrupts = if index == 0 {
+                    interrupts
+                        .iter()
+                        .filter(|(instance, _)| *instance == Some(instance_name))
+                        .map(|(_, interrupt)| interrupt.clone())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                peripherals.push(parse_register_group(
+                    module_definition,
+                    register_group,
+                    &name,
+                    attribute_number(*group_ref, "offset")?.unwrap_or_default(),
+                    instance_interrupts,
+                    level,
+                )?);
+            }
+        }
+    }
+    let device = svd::Device::builder()
+        .name(device_name.to_owned())
+        .description(device.attribute("family").unwrap_or_default().to_owned())
+        .peripherals(peripherals)
+        .build(level)?;
+    Ok(device)
+}
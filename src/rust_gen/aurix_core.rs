@@ -0,0 +1,65 @@
+//! Detect cores of Aurix devices and their private peripherals.
+//!
+//! Core private peripherals are memory mapped peripherals named `CPU<N>` or `<NAME>_CPU<N>`.
+//! Core special function registers are the `csfr_cpu<N>` peripherals.
+use super::ir::*;
+use super::util::ToSanitizedSymbol;
+use lazy_regex::regex_captures;
+use std::collections::BTreeMap;
+
+/// Split name of core private peripheral in core local alias and core index
+fn split_core_index(name: &str) -> Option<(String, u32)> {
+    let (_, prefix, index) = regex_captures!(r"^((?:.*_)?CPU)(\d+)$"i, name)?;
+    Some((prefix.to_owned(), index.parse().ok()?))
+}
+
+/// Global address of DSPR of core. Addresses follow the memory map of TC2xx/TC3xx devices.
+fn get_dspr_address(index: u32) -> Option<u64> {
+    match index {
+        0..=4 => Some(0x7000_0000 - index as u64 * 0x1000_0000),
+        5 => Some(0x1000_0000),
+        _ => None,
+    }
+}
+
+fn get_core(cores: &mut BTreeMap<u32, AurixCore>, index: u32) -> &mut AurixCore {
+    cores.entry(index).or_insert_with(|| AurixCore {
+        index,
+        peripherals: Vec::new(),
+        csfr_module: None,
+        dspr: get_dspr_address(index),
+    })
+}
+
+/// Get cores of device sorted by index
+///
+/// # Arguments
+///
+/// * `ir` intermediate representation of memory mapped peripherals
+/// * `ir_csfr` intermediate representation of core special function registers
+pub(super) fn get_aurix_cores(ir: &IR, ir_csfr: &Option<IR>) -> Vec<AurixCore> {
+    let mut cores: BTreeMap<u32, AurixCore> = BTreeMap::new();
+    for peripheral in ir.device.peripheral_mod.values() {
+        let peripheral = peripheral.borrow();
+        if let Some((alias, index)) = split_core_index(&peripheral.name) {
+            get_core(&mut cores, index)
+                .peripherals
+                .push(CorePeripheral {
+                    name: peripheral.name.clone(),
+                    type_name: peripheral.type_name.clone(),
+                    alias,
+                });
+        }
+    }
+    for peripheral in ir_csfr
+        .iter()
+        .flat_map(|ir_csfr| ir_csfr.device.peripheral_mod.values())
+    {
+        let peripheral = peripheral.borrow();
+        if let Some((_, index)) = split_core_index(&peripheral.name) {
+            get_core(&mut cores, index).csfr_module =
+                Some(peripheral.name.to_sanitized_mod_ident());
+        }
+    }
+    cores.into_values().collect()
+}
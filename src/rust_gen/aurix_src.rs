@@ -0,0 +1,126 @@
+//! Collect service request nodes of Aurix SRC peripheral.
+//!
+//! Every register of SRC peripheral that contains a SRPN bitfield is considered
+//! a service request node. The peripheral raising the request is detected from the name of node.
+use super::ir::*;
+use linked_hash_map::LinkedHashMap;
+use log::{info, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Name of peripheral that contains service request control registers
+const SRC_PERIPHERAL_NAME: &str = "SRC";
+
+/// Bitfields of service request control register required by generated helpers
+const SRC_BITFIELDS: [&str; 6] = ["SRPN", "SRE", "TOS", "SRR", "CLRR", "SETR"];
+
+/// Collect name and offset of all registers containing SRPN bitfield.
+/// Name is built concatenating names of clusters and register, cluster and register arrays are unrolled.
+fn collect_service_request_registers(
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    name_prefix: &str,
+    base_offset: u64,
+    result: &mut Vec<(String, u64, Rc<RefCell<Register>>)>,
+) {
+    for register_rc in registers.values() {
+        let register = register_rc.borrow();
+        if !register.fields.contains_key("SRPN") {
+            continue;
+        }
+        for index in 0..register.dim {
+            let name = if register.dim == 1 {
+                format!("{name_prefix}{}", register.name)
+            } else {
+                format!("{name_prefix}{}{index}", register.name)
+            };
+            let offset =
+                base_offset + register.offset as u64 + (index * register.dim_increment) as u64;
+            result.push((name, offset, register_rc.clone()));
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            let name_prefix = if cluster.dim == 1 {
+                format!("{name_prefix}{}_", cluster.name)
+            } else {
+                format!("{name_prefix}{}{index}_", cluster.name)
+            };
+            collect_service_request_registers(
+                &cluster.registers,
+                &cluster.clusters,
+                &name_prefix,
+                base_offset + cluster.offset as u64 + (index * cluster.dim_increment) as u64,
+                result,
+            );
+        }
+    }
+}
+
+/// Find the peripheral with longest name that is a prefix of service request node name
+fn get_requesting_peripheral(device: &Device, node_name: &str) -> String {
+    let node_name = node_name.to_uppercase();
+    device
+        .peripheral_mod
+        .values()
+        .map(|peripheral| peripheral.borrow().name.to_uppercase())
+        .filter(|name| name != SRC_PERIPHERAL_NAME && node_name.starts_with(name.as_str()))
+        .max_by_key(|name| name.len())
+        .unwrap_or_default()
+}
+
+/// Create table of service request nodes of Aurix SRC peripheral
+///
+/// # Result
+///
+/// `None` if device has no SRC peripheral or its registers don't contain all required bitfields
+pub(super) fn get_service_request_table(device: &Device) -> Option<ServiceRequestTable> {
+    let src_peripheral = device.peripheral_mod.values().find(|peripheral| {
+        peripheral
+            .borrow()
+            .name
+            .eq_ignore_ascii_case(SRC_PERIPHERAL_NAME)
+    })?;
+    let src_peripheral = src_peripheral.borrow();
+    let Some(base_addr) = src_peripheral.base_addr.first() else {
+        warn!("SRC peripheral without base address. Service request helpers not generated");
+        return None;
+    };
+    let mut registers = Vec::new();
+    collect_service_request_registers(
+        &src_peripheral.registers,
+        &src_peripheral.clusters,
+        "",
+        0,
+        &mut registers,
+    );
+    let (_, _, first_register) = registers.first()?;
+    let mut bitfields = LinkedHashMap::new();
+    for bitfield_name in SRC_BITFIELDS {
+        match first_register.borrow().fields.get(bitfield_name) {
+            Some(field) => {
+                bitfields.insert(bitfield_name.to_owned(), field.borrow().clone());
+            }
+            None => {
+                warn!("Bitfield {bitfield_name} not found in service request control registers. Service request helpers not generated");
+                return None;
+            }
+        }
+    }
+    let nodes = registers
+        .into_iter()
+        .map(|(name, offset, _)| ServiceRequest {
+            peripheral: get_requesting_peripheral(device, &name),
+            name,
+            index: (offset / 4) as u32,
+            address: base_addr + offset,
+        })
+        .collect::<Vec<_>>();
+    info!("Found {} service request nodes", nodes.len());
+    Some(ServiceRequestTable {
+        peripheral_name: src_peripheral.name.clone(),
+        nodes,
+        bitfields,
+    })
+}
@@ -0,0 +1,158 @@
+//! Read SVD files from CMSIS-Pack archives, unpacked packs and pdsc files.
+//!
+//! The pack description (`.pdsc`) is searched for the device and the SVD file referenced by
+//! the `svd` attribute of the `debug` element of the device or of its parent family/subfamily is read.
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use roxmltree::Node;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Find pdsc file in folder containing an unpacked pack
+fn find_pdsc(folder: &Path) -> Option<PathBuf> {
+    fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|entry| {
+            entry
+                .extension()
+                .is_some_and(|extension| extension == "pdsc")
+        })
+        // Order of directory entries depends on file system
+        .min()
+}
+
+/// Check if path is a CMSIS-Pack archive, a pdsc file or a folder containing an unpacked pack
+pub(super) fn is_cmsis_pack(path: &Path) -> bool {
+    if path.is_dir() {
+        return find_pdsc(path).is_some();
+    }
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("pack") | Some("pdsc")
+    )
+}
+
+/// Get path of SVD file from `debug` element that is a child of `node`
+fn debug_svd<'a>(node: Node<'a, 'a>) -> Option<&'a str> {
+    node.children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "debug")
+        .find_map(|debug| debug.attribute("svd"))
+}
+
+/// Get name of all devices with the path of their SVD file as defined in pdsc.
+fn get_device_svd_paths(pdsc: &str) -> Result<Vec<(String, String)>> {
+    let document = roxmltree::Document::parse(pdsc).context("Invalid pdsc file")?;
+    let mut result = Vec::new();
+    for device in document
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "device")
+    {
+        let Some(name) = device.attribute("Dname") else {
+            continue;
+        };
+        // debug element can be defined for device, subFamily or family
+        if let Some(svd) = device.ancestors().find_map(debug_svd) {
+            result.push((name.to_owned(), svd.replace('\\', "/")));
+        }
+    }
+    Ok(result)
+}
+
+/// Select SVD path of device. If no device is specified the pack shall contain only one SVD file.
+fn select_svd_path(pdsc: &str, device_name: Option<&str>) -> Result<String> {
+    let devices = get_device_svd_paths(pdsc)?;
+    let device_names = || {
+        devices
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match device_name {
+        Some(device_name) => devices
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(device_name))
+            .map(|(_, svd)| svd.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Device {device_name} not found in CMSIS-Pack. Available devices: {}",
+                    device_names()
+                )
+            }),
+        None => {
+            let mut svd_paths: Vec<_> = devices.iter().map(|(_, svd)| svd.clone()).collect();
+            svd_paths.sort();
+            svd_paths.dedup();
+            match svd_paths.len() {
+                0 => Err(anyhow!("No SVD file referenced in CMSIS-Pack")),
+                1 => Ok(svd_paths.remove(0)),
+                _ => Err(anyhow!(
+                    "CMSIS-Pack contains multiple SVD files. Select device with --pack-device. Available devices: {}",
+                    device_names()
+                )),
+            }
+        }
+    }
+}
+
+/// Read SVD from folder containing unpacked pack or from pdsc file
+fn read_svd_from_folder(path: &Path, device_name: Option<&str>, xml: &mut String) -> Result<()> {
+    let pdsc_path = if path.is_dir() {
+        find_pdsc(path).context("No pdsc file found in CMSIS-Pack folder")?
+    } else {
+        path.to_owned()
+    };
+    let pdsc = fs::read_to_string(&pdsc_path).context("Cannot read pdsc file")?;
+    let svd_path = select_svd_path(&pdsc, device_name)?;
+    let pack_folder = pdsc_path.parent().unwrap_or(Path::new("."));
+    info!("Reading SVD file {} from CMSIS-Pack", svd_path);
+    File::open(pack_folder.join(&svd_path))
+        .with_context(|| format!("Cannot open SVD file {svd_path} of CMSIS-Pack"))?
+        .read_to_string(xml)
+        .context("Cannot read SVD file of CMSIS-Pack")?;
+    Ok(())
+}
+
+/// Read SVD from `.pack` archive
+fn read_svd_from_archive(path: &Path, device_name: Option<&str>, xml: &mut String) -> Result<()> {
+    let file = File::open(path).context("Cannot open CMSIS-Pack file")?;
+    let mut archive = zip::ZipArchive::new(file).context("Invalid CMSIS-Pack archive")?;
+    let pdsc_name = archive
+        .file_names()
+        .find(|name| name.ends_with(".pdsc") && !name.contains('/'))
+        .context("No pdsc file found in CMSIS-Pack archive")?
+        .to_owned();
+    let mut pdsc = String::new();
+    archive
+        .by_name(&pdsc_name)?
+        .read_to_string(&mut pdsc)
+        .context("Cannot read pdsc file of CMSIS-Pack")?;
+    let svd_path = select_svd_path(&pdsc, device_name)?;
+    info!("Reading SVD file {} from CMSIS-Pack", svd_path);
+    archive
+        .by_name(svd_path.trim_start_matches("./"))
+        .with_context(|| format!("SVD file {svd_path} not found in CMSIS-Pack archive"))?
+        .read_to_string(xml)
+        .context("Cannot read SVD file of CMSIS-Pack")?;
+    Ok(())
+}
+
+/// Read SVD file of device from CMSIS-Pack
+///
+/// # Arguments
+///
+/// * `path` path of `.pack` archive, `.pdsc` file or folder containing unpacked pack
+/// * `device_name` name of device. Can be omitted if pack contains only one SVD file.
+/// * `xml` string where the content of SVD file is stored
+pub(super) fn read_svd(path: &Path, device_name: Option<&str>, xml: &mut String) -> Result<()> {
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "pack")
+    {
+        read_svd_from_archive(path, device_name, xml)
+    } else {
+        read_svd_from_folder(path, device_name, xml)
+    }
+}
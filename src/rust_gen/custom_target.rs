@@ -0,0 +1,131 @@
+//! Target definitions loaded at runtime.
+//!
+//! A target definition is a folder containing `target.toml` and Tera templates.
+//! Templates with the same name of builtin ones (e.g. `lib.tera`) replace them,
+//! all other templates can be imported by templates or rendered to additional files.
+//! Additional variables of templates can be loaded from a separate TOML or JSON file.
+use crate::Target;
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use log::info;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::Tera;
+
+/// Name of target definition file when a folder is specified
+const TARGET_DEFINITION_FILE_NAME: &str = "target.toml";
+
+/// Additional file rendered with a template of target definition
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) struct OutputFile {
+    /// Name of template in target definition folder
+    pub template: String,
+    /// Path of generated file relative to destination folder
+    pub output: PathBuf,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct TargetDefinitionToml {
+    name: String,
+    base: Option<String>,
+    default_register_size: Option<u32>,
+    #[serde(default)]
+    context: toml::Table,
+    #[serde(default)]
+    files: Vec<OutputFile>,
+}
+
+/// Target definition loaded from file
+#[derive(Debug)]
+pub(super) struct TargetDefinition {
+    pub name: String,
+    /// Builtin target whose templates and settings are used if not overridden
+    pub base: Target,
+    /// Size of registers if not specified in register description
+    pub default_register_size: Option<u32>,
+    /// Values available in templates as `custom.<key>`
+    pub context: toml::Table,
+    pub files: Vec<OutputFile>,
+    /// Folder containing templates
+    pub folder: PathBuf,
+}
+
+/// Load target definition from folder containing `target.toml` or from TOML file
+pub(super) fn load_target_definition(path: &Path) -> Result<TargetDefinition> {
+    let definition_path = if path.is_dir() {
+        path.join(TARGET_DEFINITION_FILE_NAME)
+    } else {
+        path.to_owned()
+    };
+    info!("Reading target definition {}", definition_path.display());
+    let definition_text = fs::read_to_string(&definition_path).with_context(|| {
+        format!(
+            "Cannot read target definition {}",
+            definition_path.display()
+        )
+    })?;
+    let definition: TargetDefinitionToml =
+        toml::from_str(&definition_text).context("Invalid target definition")?;
+    let base = match definition.base {
+        None => Target::Generic,
+        Some(ref base) => <Target as ValueEnum>::from_str(base, true)
+            .map_err(|err| anyhow!("Invalid base target {base} in target definition: {err}"))?,
+    };
+    Ok(TargetDefinition {
+        name: definition.name,
+        base,
+        default_register_size: definition.default_register_size,
+        context: definition.context,
+        files: definition.files,
+        folder: definition_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_owned(),
+    })
+}
+
+/// Load additional variables of templates from TOML file or JSON file if extension is `.json`
+pub(super) fn load_template_context(
+    path: &Path,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    info!("Reading template context {}", path.display());
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Cannot read template context {}", path.display()))?;
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        serde_json::from_str(&text)
+            .with_context(|| format!("Invalid JSON template context {}", path.display()))
+    } else {
+        toml::from_str(&text)
+            .with_context(|| format!("Invalid TOML template context {}", path.display()))
+    }
+}
+
+/// Add all templates of target definition folder to Tera.
+/// Templates with the same name of builtin ones replace them.
+pub(super) fn add_templates(tera: &mut Tera, definition: &TargetDefinition) -> Result<()> {
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(&definition.folder).context("Cannot read target definition folder")? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "tera")
+        {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .context("Invalid template file name")?
+                .to_owned();
+            info!("Load template {} of target {}", name, definition.name);
+            templates.push((path, Some(name)));
+        }
+    }
+    tera.add_template_files(templates)
+        .context("Invalid template in target definition")?;
+    Ok(())
+}
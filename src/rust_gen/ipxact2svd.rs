@@ -0,0 +1,280 @@
+//! Convert IP-XACT (IEEE 1685-2009 and IEEE 1685-2014) component description to svd device.
+//!
+//! Each `addressBlock` of the memory maps is converted to a peripheral and each `registerFile`
+//! to a cluster. Afterwards the same intermediate representation used for SVD files is created.
+use super::xml2ir::get_validate_level;
+use crate::SvdValidationLevel;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use roxmltree::Node;
+use svd_parser::svd::{self, ValidateLevel};
+
+/// Get first child element with given local name. Namespace prefix (`ipxact:` or `spirit:`) is ignored.
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Get all children elements with given local name.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Get trimmed text of first child element with given local name.
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|n| n.text())
+        .map(|s| s.trim().to_owned())
+}
+
+/// Parse a number using IP-XACT notation. Supported formats are decimal, `0x` prefixed hexadecimal
+/// and verilog style literals like `'h1F` or `32'h1F`.
+fn parse_number(value: &str) -> Result<u64> {
+    let value = value.trim().replace('_', "");
+    let (digits, radix) = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        (hex.to_owned(), 16)
+    } else if let Some((_, literal)) = value.split_once('\'') {
+        let literal = literal.trim_start_matches(['s', 'S']);
+        let radix = match literal.chars().next() {
+            Some('h') | Some('H') => 16,
+            Some('d') | Some('D') => 10,
+            Some('o') | Some('O') => 8,
+            Some('b') | Some('B') => 2,
+            _ => return Err(anyhow!("Invalid number literal {value}")),
+        };
+        (literal[1..].to_owned(), radix)
+    } else {
+        (value.clone(), 10)
+    };
+    u64::from_str_radix(&digits, radix).context(format!("Invalid number {value}"))
+}
+
+/// Get number stored in first child element with given local name.
+fn child_number(node: Node, name: &str) -> Result<Option<u64>> {
+    child_text(node, name).map(|s| parse_number(&s)).transpose()
+}
+
+fn parse_access(node: Node) -> Option<svd::Access> {
+    match child_text(node, "access")?.as_str() {
+        "read-only" => Some(svd::Access::ReadOnly),
+        "write-only" => Some(svd::Access::WriteOnly),
+        "read-write" => Some(svd::Access::ReadWrite),
+        "writeOnce" => Some(svd::Access::WriteOnce),
+        "read-writeOnce" => Some(svd::Access::ReadWriteOnce),
+        access => {
+            warn!("Unsupported access {access}. Access inherited from parent");
+            None
+        }
+    }
+}
+
+/// Get reset value of a register or field. IP-XACT 2014 uses `resets/reset/value`, IP-XACT 2009 uses `reset/value`
+fn parse_reset_value(node: Node) -> Result<Option<u64>> {
+    let reset = child(node, "resets")
+        .and_then(|resets| child(resets, "reset"))
+        .or_else(|| child(node, "reset"));
+    match reset {
+        Some(reset) => child_number(reset, "value"),
+        None => Ok(None),
+    }
+}
+
+fn parse_enumerated_values(node: Node, level: ValidateLevel) -> Result<Vec<svd::EnumeratedValues>> {
+    let Some(enumerated_values) = child(node, "enumeratedValues") else {
+        return Ok(Vec::new());
+    };
+    let mut values = Vec::new();
+    for value in children(enumerated_values, "enumeratedValue") {
+        values.push(
+            svd::EnumeratedValue::builder()
+                .name(child_text(value, "name").context("enumeratedValue without name")?)
+                .description(child_text(value, "description"))
+                .value(child_number(value, "value")?)
+                .build(level)?,
+        );
+    }
+    Ok(vec![svd::EnumeratedValues::builder()
+        .values(values)
+        .build(level)?])
+}
+
+fn parse_field(node: Node, level: ValidateLevel) -> Result<svd::Field> {
+    let name = child_text(node, "name").context("field without name")?;
+    let offset = child_number(node, "bitOffset")?
+        .with_context(|| format!("field {name} without bitOffset"))?;
+    let width = child_number(node, "bitWidth")?
+        .with_context(|| format!("field {name} without bitWidth"))?;
+    let field = svd::FieldInfo::builder()
+        .description(child_text(node, "description"))
+        .bit_range(svd::BitRange::from_offset_width(
+            offset as u32,
+            width as u32,
+        ))
+        .access(parse_access(node))
+        .enumerated_values(parse_enumerated_values(node, level)?)
+        .name(name)
+        .build(level)?;
+    Ok(svd::Field::Single(field))
+}
+
+fn parse_register(node: Node, level: ValidateLevel) -> Result<svd::RegisterCluster> {
+    let name = child_text(node, "name").context("register without name")?;
+    debug!("Parsing IP-XACT register: {}", name);
+    let fields = children(node, "field")
+        .map(|field| parse_field(field, level))
+        .collect::<Result<Vec<_>>>()?;
+    // IP-XACT 2014 defines reset values only for fields
+    let reset_value = match parse_reset_value(node)? {
+        Some(reset_value) => reset_value,
+        None => children(node, "field").try_fold(0u64, |acc, field| -> Result<u64> {
+            let offset = child_number(field, "bitOffset")?.unwrap_or_default();
+            let reset_value = parse_reset_value(field)?.unwrap_or_default();
+            let shifted_reset_value = u32::try_from(offset)
+                .ok()
+                .and_then(|offset| reset_value.checked_shl(offset))
+                .with_context(|| {
+                    format!("Reset value of a field of register {name} at bit offset {offset} exceeds 64 bits")
+                })?;
+            Ok(acc | shifted_reset_value)
+        })?,
+    };
+    let size = child_number(node, "size")?.map(|size| size as u32);
+    // Multidimensional arrays are flattened in a single dimension array.
+    // Elements of IP-XACT register arrays are contiguous.
+    let dim = children(node, "dim")
+        .map(|dim| dim.text().map(parse_number).transpose())
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .reduce(|acc, dim| acc * dim);
+    let register = svd::RegisterInfo::builder()
+        .description(child_text(node, "description"))
+        .address_offset(
+            child_number(node, "addressOffset")?
+                .with_context(|| format!("register {name} without addressOffset"))?
+                as u32,
+        )
+        .size(size)
+        .access(parse_access(node))
+        .reset_value(Some(reset_value))
+        .fields(if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        });
+    let register = match dim {
+        None => svd::Register::Single(register.name(name).build(level)?),
+        Some(dim) => {
+            let size = size.with_context(|| format!("register array {name} without size"))?;
+            let dim_element = svd::DimElement::builder()
+                .dim(dim as u32)
+                .dim_increment(size / 8)
+                .build(level)?;
+            svd::Register::Array(
+                register.name(format!("{name}[%s]")).build(level)?,
+                dim_element,
+            )
+        }
+    };
+    Ok(svd::RegisterCluster::Register(register))
+}
+
+/// Parse registers and register files contained in an address block or register file.
+fn parse_registers(node: Node, level: ValidateLevel) -> Result<Vec<svd::RegisterCluster>> {
+    let mut result = Vec::new();
+    for item in node.children().filter(|n| n.is_element()) {
+        match item.tag_name().name() {
+            "register" => result.push(parse_register(item, level)?),
+            "registerFile" => result.push(parse_register_file(item, level)?),
+            _ => continue,
+        }
+    }
+    Ok(result)
+}
+
+fn parse_register_file(node: Node, level: ValidateLevel) -> Result<svd::RegisterCluster> {
+    let name = child_text(node, "name").context("registerFile without name")?;
+    if child(node, "dim").is_some() {
+        warn!(
+            "Arrays of registerFile are not supported. Only first element of {name} is generated"
+        );
+    }
+    let cluster = svd::ClusterInfo::builder()
+        .description(child_text(node, "description"))
+        .address_offset(
+            child_number(node, "addressOffset")?
+                .with_context(|| format!("registerFile {name} without addressOffset"))?
+                as u32,
+        )
+        .children(parse_registers(node, level)?)
+        .name(name)
+        .build(level)?;
+    Ok(svd::RegisterCluster::Cluster(svd::Cluster::Single(cluster)))
+}
+
+fn parse_address_block(node: Node, level: ValidateLevel) -> Result<svd::Peripheral> {
+    let name = child_text(node, "name").context("addressBlock without name")?;
+    debug!("Parsing IP-XACT address block: {}", name);
+    let range = child_number(node, "range")?
+        .with_context(|| format!("addressBlock {name} without range"))?;
+    let address_block = svd::AddressBlock::builder()
+        .offset(0)
+        .size(range as u32)
+        .usage(svd::AddressBlockUsage::Registers)
+        .build(level)?;
+    let peripheral = svd::PeripheralInfo::builder()
+        .description(child_text(node, "description"))
+        .base_address(
+            child_number(node, "baseAddress")?
+                .with_context(|| format!("addressBlock {name} without baseAddress"))?,
+        )
+        .address_block(Some(vec![address_block]))
+        .registers(Some(parse_registers(node, level)?))
+        .name(name)
+        .build(level)?;
+    Ok(svd::Peripheral::Single(peripheral))
+}
+
+/// Parse IP-XACT xml and transform it to device description of svd_rs module
+///
+/// # Arguments
+///
+/// * `xml` content of IP-XACT component file
+///
+/// # Result
+///
+/// Device containing all address blocks of component as peripherals
+pub(super) fn parse_ipxact(
+    xml: &str,
+    svd_validation_level: SvdValidationLevel,
+) -> Result<svd::Device> {
+    let level = get_validate_level(svd_validation_level);
+    let document = roxmltree::Document::parse(xml).context("Invalid IP-XACT xml")?;
+    let component = document.root_element();
+    if component.tag_name().name() != "component" {
+        return Err(anyhow!("IP-XACT root element shall be component"));
+    }
+    let name = child_text(component, "name").context("component without name")?;
+    let mut peripherals = Vec::new();
+    if let Some(memory_maps) = child(component, "memoryMaps") {
+        for memory_map in children(memory_maps, "memoryMap") {
+            for address_block in children(memory_map, "addressBlock") {
+                peripherals.push(parse_address_block(address_block, level)?);
+            }
+        }
+    }
+    let device = svd::Device::builder()
+        .version(child_text(component, "version").unwrap_or_default())
+        .description(child_text(component, "description").unwrap_or_default())
+        .peripherals(peripherals)
+        .name(name)
+        .build(level)?;
+    Ok(device)
+}
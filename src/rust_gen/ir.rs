@@ -60,6 +60,8 @@ pub struct FieldGetterSetter {
     pub description: String,
     pub offset: u32,
     pub mask: u32,
+    /// Number of bits of bitfield
+    pub width: u32,
     pub size: BitSize,
     pub enum_type: Option<EnumeratedValueType>,
     pub access: RegisterBitfieldAccess,
@@ -93,6 +95,15 @@ pub enum BitSize {
 }
 
 impl BitSize {
+    /// Number of bytes required to store a value of this size
+    pub fn byte_size(&self) -> u64 {
+        match self {
+            BitSize::BIT64 => 8,
+            BitSize::BIT32 => 4,
+            BitSize::BIT16 => 2,
+            BitSize::BIT8 => 1,
+        }
+    }
     pub fn val_2_bit_size(val: u64) -> BitSize {
         if val <= u8::MAX.into() {
             BitSize::BIT8
@@ -113,10 +124,22 @@ pub struct Register {
     pub offset: u32,
     pub dim: u32,
     pub dim_increment: u32,
+    /// Index of each element of array used to substitute `%s` in description. Empty if not an array.
+    pub dim_index: Vec<String>,
     pub access: RegisterAccess,
     pub fields: LinkedHashMap<String, Rc<RefCell<FieldGetterSetter>>>,
     pub size: BitSize,
     pub reset_value: u64,
+    /// Bits of register that read back the value written. Used to verify write operations.
+    pub verify_mask: u64,
+    /// Bits of register that belong to a bitfield, elements of bitfield arrays included
+    pub fields_mask: u64,
+    /// Reading register or one of its bitfields modifies its content
+    pub has_read_side_effects: bool,
+    /// Register declares `alternateRegister` or `alternateGroup`
+    pub is_alternate: bool,
+    /// Names of registers at the same address that are not declared as alternate registers
+    pub aliases: Vec<String>,
     pub has_enumerated_fields: bool,
     pub is_derived_from: bool,
     /// Full Rust path to module that contains the struct
@@ -132,9 +155,13 @@ pub struct Cluster {
     pub offset: u32,
     pub dim: u32,
     pub dim_increment: u32,
+    /// Index of each element of array used to substitute `%s` in description. Empty if not an array.
+    pub dim_index: Vec<String>,
     pub registers: LinkedHashMap<String, Rc<RefCell<Register>>>,
     pub clusters: LinkedHashMap<String, Rc<RefCell<Cluster>>>,
     pub is_derived_from: bool,
+    /// Struct of cluster is used also by other clusters with same layout
+    pub is_shared: bool,
     /// Full Rust path to module that contains the struct
     pub struct_module_path: Vec<String>,
     /// Id of the struct
@@ -143,6 +170,13 @@ pub struct Cluster {
     pub module_id: String,
 }
 
+/// Address block of a peripheral. Offset is relative to base address of peripheral.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressBlock {
+    pub offset: u32,
+    pub size: u32,
+}
+
 /// Describe Rust module that maps to a peripheral
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PeripheralMod {
@@ -151,8 +185,18 @@ pub struct PeripheralMod {
     pub clusters: LinkedHashMap<String, Rc<RefCell<Cluster>>>,
     pub registers: LinkedHashMap<String, Rc<RefCell<Register>>>,
     pub base_addr: Vec<u64>,
+    /// Base addresses of secure alias. Empty if TrustZone aliases are not generated.
+    pub secure_base_addr: Vec<u64>,
+    /// Base addresses of non-secure alias. Empty if TrustZone aliases are not generated.
+    pub non_secure_base_addr: Vec<u64>,
+    pub address_blocks: Vec<AddressBlock>,
     pub interrupts: Vec<Interrupt>,
+    /// All instances of peripheral are inside a bit-band region of Cortex-M3/M4.
+    pub bit_band: bool,
     pub is_derived_from: bool,
+    /// Name of peripheral whose struct is used by this peripheral.
+    /// Peripherals derived from another one with same registers share its struct.
+    pub type_name: String,
     // Struct identifier of the peripheral.
     pub struct_id: String,
     // It could be different from name lower case if derived_from is used.
@@ -182,6 +226,148 @@ pub struct Interrupt {
     pub description: String,
 }
 
+/// Step of path from peripheral to a register, used to generate register dump
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DumpPathStep {
+    /// Name of cluster or register
+    pub name: String,
+    /// Index in cluster or register array
+    pub index: Option<u32>,
+}
+
+/// Register included in peripheral dump
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DumpRegister {
+    /// Name of register relative to peripheral. Clusters are separated by `.`
+    pub name: String,
+    /// Clusters and register to traverse to access register
+    pub path: Vec<DumpPathStep>,
+}
+
+/// Field of `#[repr(C)]` raw view of peripheral
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RawField {
+    pub name: String,
+    pub description: String,
+    /// Offset from start of peripheral
+    pub offset: u64,
+    /// Size of element
+    pub size: BitSize,
+    /// Number of elements if field is an array
+    pub len: Option<u32>,
+    /// Padding between registers
+    pub is_reserved: bool,
+}
+
+/// `#[repr(C)]` raw view of peripheral
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RawLayout {
+    pub fields: Vec<RawField>,
+    /// Size of struct in bytes
+    pub size: u64,
+}
+
+/// Service request node of Aurix SRC peripheral
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServiceRequest {
+    /// Name of node built from names of clusters and register in SRC peripheral
+    pub name: String,
+    /// Name of peripheral that raises the service request. Empty if it cannot be detected
+    pub peripheral: String,
+    /// Service request node number. It is the offset of register in SRC peripheral divided by 4
+    pub index: u32,
+    pub address: u64,
+}
+
+/// Service request nodes of Aurix SRC peripheral used to generate routing helpers
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServiceRequestTable {
+    /// Name of SRC peripheral
+    pub peripheral_name: String,
+    pub nodes: Vec<ServiceRequest>,
+    /// Bitfields of service request control register (SRPN, SRE, TOS, SRR, CLRR, SETR)
+    pub bitfields: LinkedHashMap<String, FieldGetterSetter>,
+}
+
+/// Register of mock register file used when registers are mocked on host
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MockRegister {
+    pub address: u64,
+    /// Size of register in bytes
+    pub size: u64,
+    pub reset_value: u64,
+}
+
+/// Register stored in peripheral of simulator crate
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimRegister {
+    /// Name of register relative to peripheral. Clusters are separated by `.`
+    pub name: String,
+    /// Identifier of field that stores the value of register
+    pub ident: String,
+    /// Offset of register from base address of peripheral
+    pub offset: u64,
+    pub size: BitSize,
+    pub reset_value: u64,
+}
+
+/// Peripheral of simulator crate
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimPeripheral {
+    pub name: String,
+    /// Base addresses of instances of peripheral
+    pub base_addr: Vec<u64>,
+    /// Registers sorted by offset
+    pub registers: Vec<SimRegister>,
+}
+
+/// Items of a peripheral re-exported by main crate of workspace
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceExport {
+    /// Feature of peripheral
+    pub feature: String,
+    /// Feature of peripheral that defines the module used by this one, if derived
+    pub parent: Option<String>,
+    /// Module, struct and instances of peripheral
+    pub items: Vec<String>,
+}
+
+/// Crate of workspace with one or more peripheral modules
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceCrate {
+    /// Package name
+    pub package: String,
+    /// Name of crate in Rust code
+    pub ident: String,
+    /// Folder of crate relative to workspace root
+    pub path: String,
+    /// Ids of peripheral modules of crate
+    pub modules: Vec<String>,
+    pub exports: Vec<WorkspaceExport>,
+}
+
+/// Peripheral private to a core of Aurix device
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CorePeripheral {
+    /// Name of peripheral instance
+    pub name: String,
+    /// Name of peripheral whose struct is used by the instance
+    pub type_name: String,
+    /// Name of core local alias. It is the name of peripheral without core index
+    pub alias: String,
+}
+
+/// Core of Aurix device with its private peripherals
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AurixCore {
+    pub index: u32,
+    pub peripherals: Vec<CorePeripheral>,
+    /// Name of module with core special function registers of core
+    pub csfr_module: Option<String>,
+    /// Global address of data scratch pad RAM of core. `None` if it is unknown.
+    pub dspr: Option<u64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct IR {
     pub device: Device,
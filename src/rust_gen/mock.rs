@@ -0,0 +1,63 @@
+//! Collect registers of mock register file.
+//!
+//! Mock register file contains one entry for each address of device that hosts a register.
+//! Peripheral, cluster and register arrays are unrolled.
+use super::ir::*;
+use linked_hash_map::LinkedHashMap;
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Collect address, size and reset value of registers.
+fn collect_mock_registers(
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    base_address: u64,
+    result: &mut Vec<MockRegister>,
+) {
+    for register in registers.values() {
+        let register = register.borrow();
+        for index in 0..register.dim {
+            result.push(MockRegister {
+                address: base_address
+                    + register.offset as u64
+                    + (index * register.dim_increment) as u64,
+                size: register.size.byte_size(),
+                reset_value: register.reset_value,
+            });
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            collect_mock_registers(
+                &cluster.registers,
+                &cluster.clusters,
+                base_address + cluster.offset as u64 + (index * cluster.dim_increment) as u64,
+                result,
+            );
+        }
+    }
+}
+
+/// Create table of mock register file sorted by address.
+/// If more registers share the same address, the first one declared is used.
+pub(super) fn get_mock_registers(device: &Device) -> Vec<MockRegister> {
+    let mut result = Vec::new();
+    for peripheral in device.peripheral_mod.values() {
+        let peripheral = peripheral.borrow();
+        for base_address in &peripheral.base_addr {
+            collect_mock_registers(
+                &peripheral.registers,
+                &peripheral.clusters,
+                *base_address,
+                &mut result,
+            );
+        }
+    }
+    // Stable sort keeps the declaration order of registers at same address
+    result.sort_by_key(|register| register.address);
+    result.dedup_by_key(|register| register.address);
+    info!("Mock register file contains {} registers", result.len());
+    result
+}
@@ -0,0 +1,498 @@
+//! Apply svdtools compatible YAML patch files to device description.
+//!
+//! Supported subset of svdtools format:
+//! * device level: `_include`, `_delete`, `_modify`, `_add` and peripheral patches
+//! * peripheral and cluster level: `_delete`, `_modify`, `_add`, `_strip`, `_strip_end`, `_cluster` and register patches
+//! * register level: `_delete`, `_modify`, `_add` and field enumerated values
+//!
+//! Names of peripherals, clusters, registers and fields can be specified using globs (`*`, `?`)
+//! and comma separated lists as in svdtools.
+use anyhow::{anyhow, Context, Result};
+use lazy_regex::Regex;
+use log::{debug, warn};
+use serde_yaml_ng::{Mapping, Value};
+use std::fs;
+use std::path::Path;
+use svd_parser::svd::{self, ValidateLevel};
+
+/// Convert svdtools glob (e.g. `TIM[1-3],UART*`) to regular expression matching the whole name.
+pub(crate) fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let alternatives: Vec<String> = glob
+        .split(',')
+        .map(|alternative| {
+            let mut regex = String::new();
+            for c in alternative.trim().chars() {
+                match c {
+                    '*' => regex.push_str(".*"),
+                    '?' => regex.push('.'),
+                    '.' | '+' | '(' | ')' | '|' | '{' | '}' | '^' | '$' | '\\' => {
+                        regex.push('\\');
+                        regex.push(c);
+                    }
+                    c => regex.push(c),
+                }
+            }
+            regex
+        })
+        .collect();
+    Regex::new(&format!("^(?:{})$", alternatives.join("|")))
+        .with_context(|| format!("Invalid name pattern {glob}"))
+}
+
+fn key_str(key: &Value) -> Result<&str> {
+    key.as_str()
+        .ok_or_else(|| anyhow!("Patch key {key:?} shall be a string"))
+}
+
+fn value_str(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => Err(anyhow!("Patch value {value:?} shall be a string")),
+    }
+}
+
+/// Numbers can be specified as yaml integer or as string with `0x` prefix.
+fn value_u64(value: &Value) -> Result<u64> {
+    if let Some(number) = value.as_u64() {
+        return Ok(number);
+    }
+    let text = value
+        .as_str()
+        .ok_or_else(|| anyhow!("Patch value {value:?} shall be a number"))?
+        .trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    }
+    .with_context(|| format!("Invalid number {text}"))
+}
+
+fn value_access(value: &Value) -> Result<svd::Access> {
+    let access = value_str(value)?;
+    svd::Access::parse_str(&access).ok_or_else(|| anyhow!("Invalid access {access}"))
+}
+
+fn value_mapping<'a>(value: &'a Value, context: &str) -> Result<&'a Mapping> {
+    value
+        .as_mapping()
+        .ok_or_else(|| anyhow!("{context} shall be a mapping"))
+}
+
+/// Get list of name patterns. A single string is accepted as list with one element.
+fn value_patterns(value: &Value) -> Result<Vec<Regex>> {
+    match value {
+        Value::Sequence(items) => items
+            .iter()
+            .map(|item| glob_to_regex(&value_str(item)?))
+            .collect(),
+        value => Ok(vec![glob_to_regex(&value_str(value)?)?]),
+    }
+}
+
+fn modify_peripheral(peripheral: &mut svd::PeripheralInfo, properties: &Mapping) -> Result<()> {
+    for (key, value) in properties {
+        match key_str(key)? {
+            "name" => peripheral.name = value_str(value)?,
+            "description" => peripheral.description = Some(value_str(value)?),
+            "groupName" => peripheral.group_name = Some(value_str(value)?),
+            "baseAddress" => peripheral.base_address = value_u64(value)?,
+            "size" => peripheral.default_register_properties.size = Some(value_u64(value)? as u32),
+            "access" => peripheral.default_register_properties.access = Some(value_access(value)?),
+            "resetValue" => {
+                peripheral.default_register_properties.reset_value = Some(value_u64(value)?)
+            }
+            key => warn!("Unsupported peripheral property {key} in patch"),
+        }
+    }
+    Ok(())
+}
+
+fn modify_register(register: &mut svd::RegisterInfo, properties: &Mapping) -> Result<()> {
+    for (key, value) in properties {
+        match key_str(key)? {
+            "name" => register.name = value_str(value)?,
+            "displayName" => register.display_name = Some(value_str(value)?),
+            "description" => register.description = Some(value_str(value)?),
+            "addressOffset" => register.address_offset = value_u64(value)? as u32,
+            "size" => register.properties.size = Some(value_u64(value)? as u32),
+            "access" => register.properties.access = Some(value_access(value)?),
+            "resetValue" => register.properties.reset_value = Some(value_u64(value)?),
+            "resetMask" => register.properties.reset_mask = Some(value_u64(value)?),
+            "fields" => {
+                for (name, field) in value_mapping(value, "fields")? {
+                    add_field(register, key_str(name)?, value_mapping(field, "field")?)?;
+                }
+            }
+            key => warn!("Unsupported register property {key} in patch"),
+        }
+    }
+    Ok(())
+}
+
+fn modify_field(field: &mut svd::FieldInfo, properties: &Mapping) -> Result<()> {
+    for (key, value) in properties {
+        match key_str(key)? {
+            "name" => field.name = value_str(value)?,
+            "description" => field.description = Some(value_str(value)?),
+            "access" => field.access = Some(value_access(value)?),
+            "bitOffset" => {
+                field.bit_range = svd::BitRange::from_offset_width(
+                    value_u64(value)? as u32,
+                    field.bit_range.width,
+                )
+            }
+            "bitWidth" => {
+                field.bit_range = svd::BitRange::from_offset_width(
+                    field.bit_range.offset,
+                    value_u64(value)? as u32,
+                )
+            }
+            key => warn!("Unsupported field property {key} in patch"),
+        }
+    }
+    Ok(())
+}
+
+fn add_field(register: &mut svd::RegisterInfo, name: &str, properties: &Mapping) -> Result<()> {
+    debug!("Patch: add field {} to register {}", name, register.name);
+    let mut field = svd::FieldInfo::builder()
+        .name(name.to_owned())
+        .bit_range(svd::BitRange::from_offset_width(0, 1))
+        .build(ValidateLevel::Disabled)?;
+    modify_field(&mut field, properties)?;
+    register
+        .fields
+        .get_or_insert_with(Vec::new)
+        .push(svd::Field::Single(field));
+    Ok(())
+}
+
+fn add_register(
+    registers: &mut Vec<svd::RegisterCluster>,
+    name: &str,
+    properties: &Mapping,
+) -> Result<()> {
+    debug!("Patch: add register {}", name);
+    let mut register = svd::RegisterInfo::builder()
+        .name(name.to_owned())
+        .address_offset(0)
+        .build(ValidateLevel::Disabled)?;
+    modify_register(&mut register, properties)?;
+    registers.push(svd::RegisterCluster::Register(svd::Register::Single(
+        register,
+    )));
+    Ok(())
+}
+
+fn add_peripheral(device: &mut svd::Device, name: &str, properties: &Mapping) -> Result<()> {
+    debug!("Patch: add peripheral {}", name);
+    let mut peripheral = svd::PeripheralInfo::builder()
+        .name(name.to_owned())
+        .base_address(0)
+        .build(ValidateLevel::Disabled)?;
+    for (key, value) in properties {
+        if key_str(key)? == "registers" {
+            let registers = peripheral.registers.get_or_insert_with(Vec::new);
+            for (name, register) in value_mapping(value, "registers")? {
+                add_register(
+                    registers,
+                    key_str(name)?,
+                    value_mapping(register, "register")?,
+                )?;
+            }
+        } else {
+            let mut property = Mapping::new();
+            property.insert(key.clone(), value.clone());
+            modify_peripheral(&mut peripheral, &property)?;
+        }
+    }
+    device.peripherals.push(svd::Peripheral::Single(peripheral));
+    Ok(())
+}
+
+/// Replace enumerated values of a field. Keys `_read` and `_write` define values with specific usage.
+fn patch_field_enum(field: &mut svd::FieldInfo, patch: &Mapping) -> Result<()> {
+    fn enumerated_values(
+        patch: &Mapping,
+        usage: Option<svd::Usage>,
+    ) -> Result<svd::EnumeratedValues> {
+        let mut values = Vec::new();
+        for (name, value) in patch {
+            let name = key_str(name)?;
+            if name.starts_with('_') {
+                continue;
+            }
+            // Values are specified as [value, description]
+            let (value, description) = match value {
+                Value::Sequence(items) if !items.is_empty() => (
+                    value_u64(&items[0])?,
+                    items.get(1).map(value_str).transpose()?,
+                ),
+                value => (value_u64(value)?, None),
+            };
+            values.push(
+                svd::EnumeratedValue::builder()
+                    .name(name.to_owned())
+                    .description(description)
+                    .value(Some(value))
+                    .build(ValidateLevel::Disabled)?,
+            );
+        }
+        Ok(svd::EnumeratedValues::builder()
+            .usage(usage)
+            .values(values)
+            .build(ValidateLevel::Disabled)?)
+    }
+    debug!("Patch: replace enumerated values of field {}", field.name);
+    let mut result = Vec::new();
+    for (key, usage) in [("_read", svd::Usage::Read), ("_write", svd::Usage::Write)] {
+        if let Some(values) = patch.get(key) {
+            result.push(enumerated_values(value_mapping(values, key)?, Some(usage))?);
+        }
+    }
+    if patch
+        .keys()
+        .any(|key| key.as_str().is_some_and(|key| !key.starts_with('_')))
+    {
+        result.push(enumerated_values(patch, None)?);
+    }
+    field.enumerated_values = result;
+    Ok(())
+}
+
+fn patch_register(register: &mut svd::RegisterInfo, patch: &Mapping) -> Result<()> {
+    for (key, value) in patch {
+        match key_str(key)? {
+            "_delete" => {
+                let patterns = value_patterns(value)?;
+                if let Some(fields) = register.fields.as_mut() {
+                    fields.retain(|field| !patterns.iter().any(|p| p.is_match(&field.name)));
+                }
+            }
+            "_modify" => {
+                for (name, properties) in value_mapping(value, "_modify")? {
+                    let pattern = glob_to_regex(key_str(name)?)?;
+                    let properties = value_mapping(properties, "field properties")?;
+                    for field in register.fields.iter_mut().flatten() {
+                        if pattern.is_match(&field.name) {
+                            modify_field(field, properties)?;
+                        }
+                    }
+                }
+            }
+            "_add" => {
+                for (name, properties) in value_mapping(value, "_add")? {
+                    add_field(
+                        register,
+                        key_str(name)?,
+                        value_mapping(properties, "field properties")?,
+                    )?;
+                }
+            }
+            key if key.starts_with('_') => warn!("Unsupported register patch command {key}"),
+            name => {
+                let pattern = glob_to_regex(name)?;
+                let patch = value_mapping(value, "field enumerated values")?;
+                let mut found = false;
+                for field in register.fields.iter_mut().flatten() {
+                    if pattern.is_match(&field.name) {
+                        found = true;
+                        patch_field_enum(field, patch)?;
+                    }
+                }
+                if !found {
+                    warn!(
+                        "Patch: no field {} found in register {}",
+                        name, register.name
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Patch registers and clusters of a peripheral or cluster.
+fn patch_registers(
+    parent_name: &str,
+    registers: &mut Vec<svd::RegisterCluster>,
+    patch: &Mapping,
+) -> Result<()> {
+    for (key, value) in patch {
+        match key_str(key)? {
+            "_delete" => {
+                let patterns = value_patterns(value)?;
+                registers.retain(|register| {
+                    let name = match register {
+                        svd::RegisterCluster::Register(register) => &register.name,
+                        svd::RegisterCluster::Cluster(cluster) => &cluster.name,
+                    };
+                    !patterns.iter().any(|p| p.is_match(name))
+                });
+            }
+            "_modify" => {
+                for (name, properties) in value_mapping(value, "_modify")? {
+                    let pattern = glob_to_regex(key_str(name)?)?;
+                    let properties = value_mapping(properties, "register properties")?;
+                    for register in registers.iter_mut() {
+                        if let svd::RegisterCluster::Register(register) = register {
+                            if pattern.is_match(&register.name) {
+                                modify_register(register, properties)?;
+                            }
+                        }
+                    }
+                }
+            }
+            "_add" => {
+                for (name, properties) in value_mapping(value, "_add")? {
+                    add_register(
+                        registers,
+                        key_str(name)?,
+                        value_mapping(properties, "register properties")?,
+                    )?;
+                }
+            }
+            command @ ("_strip" | "_strip_end") => {
+                let patterns: Vec<String> = match value {
+                    Value::Sequence(items) => items.iter().map(value_str).collect::<Result<_>>()?,
+                    value => vec![value_str(value)?],
+                };
+                for register in registers.iter_mut() {
+                    if let svd::RegisterCluster::Register(register) = register {
+                        for pattern in &patterns {
+                            let stripped = if command == "_strip" {
+                                register.name.strip_prefix(pattern.as_str())
+                            } else {
+                                register.name.strip_suffix(pattern.as_str())
+                            };
+                            if let Some(stripped) = stripped {
+                                register.name = stripped.to_owned();
+                            }
+                        }
+                    }
+                }
+            }
+            "_cluster" => {
+                for (name, patch) in value_mapping(value, "_cluster")? {
+                    let pattern = glob_to_regex(key_str(name)?)?;
+                    let patch = value_mapping(patch, "cluster patch")?;
+                    for cluster in registers.iter_mut() {
+                        if let svd::RegisterCluster::Cluster(cluster) = cluster {
+                            if pattern.is_match(&cluster.name) {
+                                let name = cluster.name.clone();
+                                patch_registers(&name, &mut cluster.children, patch)?;
+                            }
+                        }
+                    }
+                }
+            }
+            key if key.starts_with('_') => warn!("Unsupported peripheral patch command {key}"),
+            name => {
+                let pattern = glob_to_regex(name)?;
+                let patch = value_mapping(value, "register patch")?;
+                let mut found = false;
+                for register in registers.iter_mut() {
+                    if let svd::RegisterCluster::Register(register) = register {
+                        if pattern.is_match(&register.name) {
+                            found = true;
+                            patch_register(register, patch)?;
+                        }
+                    }
+                }
+                if !found {
+                    warn!("Patch: no register {} found in {}", name, parent_name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn patch_device(device: &mut svd::Device, patch: &Mapping, base_folder: &Path) -> Result<()> {
+    // Included patches are applied before the patch including them
+    if let Some(includes) = patch.get("_include") {
+        let includes: Vec<String> = match includes {
+            Value::Sequence(items) => items.iter().map(value_str).collect::<Result<_>>()?,
+            value => vec![value_str(value)?],
+        };
+        for include in includes {
+            apply_patch_file(device, &base_folder.join(include))?;
+        }
+    }
+    for (key, value) in patch {
+        match key_str(key)? {
+            "_svd" | "_include" => continue,
+            "_delete" => {
+                let patterns = value_patterns(value)?;
+                device
+                    .peripherals
+                    .retain(|peripheral| !patterns.iter().any(|p| p.is_match(&peripheral.name)));
+            }
+            "_modify" => {
+                for (name, properties) in value_mapping(value, "_modify")? {
+                    let name = key_str(name)?;
+                    match properties {
+                        Value::Mapping(properties) => {
+                            let pattern = glob_to_regex(name)?;
+                            for peripheral in device.peripherals.iter_mut() {
+                                if pattern.is_match(&peripheral.name) {
+                                    modify_peripheral(peripheral, properties)?;
+                                }
+                            }
+                        }
+                        value => match name {
+                            "name" => device.name = value_str(value)?,
+                            "description" => device.description = value_str(value)?,
+                            "version" => device.version = value_str(value)?,
+                            name => warn!("Unsupported device property {name} in patch"),
+                        },
+                    }
+                }
+            }
+            "_add" => {
+                for (name, properties) in value_mapping(value, "_add")? {
+                    add_peripheral(
+                        device,
+                        key_str(name)?,
+                        value_mapping(properties, "peripheral properties")?,
+                    )?;
+                }
+            }
+            key if key.starts_with('_') => warn!("Unsupported device patch command {key}"),
+            name => {
+                let pattern = glob_to_regex(name)?;
+                let patch = value_mapping(value, "peripheral patch")?;
+                let mut found = false;
+                for peripheral in device.peripherals.iter_mut() {
+                    if pattern.is_match(&peripheral.name) {
+                        found = true;
+                        let peripheral_name = peripheral.name.clone();
+                        let registers = peripheral.registers.get_or_insert_with(Vec::new);
+                        patch_registers(&peripheral_name, registers, patch)?;
+                    }
+                }
+                if !found {
+                    warn!("Patch: no peripheral {} found", name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply svdtools compatible patch file to device
+///
+/// # Arguments
+///
+/// * `device` device description to be patched
+/// * `patch_path` path of YAML patch file. Files in `_include` are relative to this file.
+pub(super) fn apply_patch_file(device: &mut svd::Device, patch_path: &Path) -> Result<()> {
+    let content = fs::read_to_string(patch_path)
+        .with_context(|| format!("Cannot read patch file {}", patch_path.display()))?;
+    let patch: Value = serde_yaml_ng::from_str(&content)
+        .with_context(|| format!("Invalid patch file {}", patch_path.display()))?;
+    let patch = value_mapping(&patch, "Patch file")?;
+    let base_folder = patch_path.parent().unwrap_or(Path::new("."));
+    patch_device(device, patch, base_folder)
+}
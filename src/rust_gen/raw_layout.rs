@@ -0,0 +1,144 @@
+//! Layout of `#[repr(C)]` raw view of peripheral registers.
+//!
+//! Registers of clusters are flattened in the raw view, arrays of registers are
+//! Rust arrays if registers are contiguous otherwise they are unrolled. Space between registers
+//! is filled with reserved byte arrays.
+use super::ir::*;
+use super::util::ToSanitizedSymbol;
+use linked_hash_map::LinkedHashMap;
+use log::warn;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Register placed in raw view before resolving overlaps and padding
+struct RawRegister {
+    name: String,
+    description: String,
+    offset: u64,
+    size: BitSize,
+    len: Option<u32>,
+}
+
+impl RawRegister {
+    fn byte_size(&self) -> u64 {
+        self.size.byte_size() * self.len.unwrap_or(1) as u64
+    }
+}
+
+/// Collect registers unrolling clusters and non contiguous register arrays
+fn collect_raw_registers(
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    name_prefix: &str,
+    base_offset: u64,
+    result: &mut Vec<RawRegister>,
+) {
+    for register in registers.values() {
+        let register = register.borrow();
+        let name = format!("{name_prefix}{}", register.name);
+        let offset = base_offset + register.offset as u64;
+        if register.dim == 1 {
+            result.push(RawRegister {
+                name,
+                description: register.description.clone(),
+                offset,
+                size: register.size.clone(),
+                len: None,
+            });
+        } else if register.dim_increment as u64 == register.size.byte_size() {
+            result.push(RawRegister {
+                name,
+                description: register.description.clone(),
+                offset,
+                size: register.size.clone(),
+                len: Some(register.dim),
+            });
+        } else {
+            for index in 0..register.dim {
+                result.push(RawRegister {
+                    name: format!("{name}{index}"),
+                    description: register
+                        .description
+                        .replace("%s", &register.dim_index[index as usize]),
+                    offset: offset + (index * register.dim_increment) as u64,
+                    size: register.size.clone(),
+                    len: None,
+                });
+            }
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            let name_prefix = if cluster.dim == 1 {
+                format!("{name_prefix}{}_", cluster.name)
+            } else {
+                format!("{name_prefix}{}{index}_", cluster.name)
+            };
+            collect_raw_registers(
+                &cluster.registers,
+                &cluster.clusters,
+                &name_prefix,
+                base_offset + cluster.offset as u64 + (index * cluster.dim_increment) as u64,
+                result,
+            );
+        }
+    }
+}
+
+/// Get `#[repr(C)]` raw view of peripheral with fields sorted by offset.
+/// Registers that overlap a previous register (e.g. alternate registers) or that are not aligned
+/// to their size are not part of the raw view.
+pub(super) fn get_raw_layout(peripheral: &PeripheralMod) -> RawLayout {
+    let mut registers = Vec::new();
+    collect_raw_registers(
+        &peripheral.registers,
+        &peripheral.clusters,
+        "",
+        0,
+        &mut registers,
+    );
+    registers.sort_by_key(|register| register.offset);
+    let mut fields = Vec::new();
+    let mut end_offset = 0;
+    let mut reserved_index = 0;
+    let mut alignment = 1;
+    for register in registers {
+        if register.offset < end_offset {
+            continue;
+        }
+        if register.offset % register.size.byte_size() != 0 {
+            warn!(
+                "Register {} of peripheral {} is not aligned to its size. Register not included in raw view",
+                register.name, peripheral.name
+            );
+            continue;
+        }
+        if register.offset > end_offset {
+            fields.push(RawField {
+                name: format!("_reserved{reserved_index}"),
+                description: String::new(),
+                offset: end_offset,
+                size: BitSize::BIT8,
+                len: Some((register.offset - end_offset) as u32),
+                is_reserved: true,
+            });
+            reserved_index += 1;
+        }
+        end_offset = register.offset + register.byte_size();
+        alignment = alignment.max(register.size.byte_size());
+        fields.push(RawField {
+            name: register.name.to_sanitized_func_ident(),
+            description: register.description,
+            offset: register.offset,
+            size: register.size,
+            len: register.len,
+            is_reserved: false,
+        });
+    }
+    RawLayout {
+        fields,
+        // Size of Rust struct is multiple of its alignment
+        size: (end_offset + alignment - 1) / alignment * alignment,
+    }
+}
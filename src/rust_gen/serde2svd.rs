@@ -0,0 +1,24 @@
+//! Read device description written in YAML or JSON.
+//!
+//! The schema is the serde representation of the `svd_rs` device model, so field names
+//! are the same used in SVD files (e.g. `baseAddress`, `addressOffset`, `bitOffset`).
+use anyhow::{Context, Result};
+use svd_parser::svd;
+
+/// Inherit register properties from parents as done for SVD files
+fn expand_device(mut device: svd::Device) -> svd::Device {
+    svd_parser::expand_properties(&mut device);
+    device
+}
+
+/// Parse YAML device description
+pub(super) fn parse_yaml(content: &str) -> Result<svd::Device> {
+    let device = serde_yaml_ng::from_str(content).context("Invalid YAML device description")?;
+    Ok(expand_device(device))
+}
+
+/// Parse JSON device description
+pub(super) fn parse_json(content: &str) -> Result<svd::Device> {
+    let device = serde_json::from_str(content).context("Invalid JSON device description")?;
+    Ok(expand_device(device))
+}
@@ -0,0 +1,99 @@
+//! Collect registers of peripherals of simulator crate.
+//!
+//! Each peripheral of simulator stores the value of all its registers.
+//! Cluster and register arrays are unrolled.
+use super::ir::*;
+use super::util::ToSanitizedSymbol;
+use linked_hash_map::LinkedHashMap;
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Collect name, offset, size and reset value of registers.
+fn collect_sim_registers(
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    offset: u64,
+    name_prefix: &str,
+    ident_prefix: &str,
+    result: &mut Vec<SimRegister>,
+) {
+    for register in registers.values() {
+        let register = register.borrow();
+        for index in 0..register.dim {
+            let (name, ident) = if register.dim == 1 {
+                (
+                    format!("{name_prefix}{}", register.name),
+                    format!("{ident_prefix}{}", register.name),
+                )
+            } else {
+                (
+                    format!("{name_prefix}{}[{index}]", register.name),
+                    format!("{ident_prefix}{}_{index}", register.name),
+                )
+            };
+            result.push(SimRegister {
+                name,
+                ident: ident.to_sanitized_mod_ident(),
+                offset: offset + register.offset as u64 + (index * register.dim_increment) as u64,
+                size: register.size.clone(),
+                reset_value: register.reset_value,
+            });
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            let (name_prefix, ident_prefix) = if cluster.dim == 1 {
+                (
+                    format!("{name_prefix}{}.", cluster.name),
+                    format!("{ident_prefix}{}_", cluster.name),
+                )
+            } else {
+                (
+                    format!("{name_prefix}{}[{index}].", cluster.name),
+                    format!("{ident_prefix}{}_{index}_", cluster.name),
+                )
+            };
+            collect_sim_registers(
+                &cluster.registers,
+                &cluster.clusters,
+                offset + cluster.offset as u64 + (index * cluster.dim_increment) as u64,
+                &name_prefix,
+                &ident_prefix,
+                result,
+            );
+        }
+    }
+}
+
+/// Create peripherals of simulator with registers sorted by offset.
+/// If more registers share the same offset, the first one declared is used.
+pub(super) fn get_sim_peripherals(device: &Device) -> Vec<SimPeripheral> {
+    let result: Vec<SimPeripheral> = device
+        .peripheral_mod
+        .values()
+        .map(|peripheral| {
+            let peripheral = peripheral.borrow();
+            let mut registers = Vec::new();
+            collect_sim_registers(
+                &peripheral.registers,
+                &peripheral.clusters,
+                0,
+                "",
+                "",
+                &mut registers,
+            );
+            // Stable sort keeps the declaration order of registers at same offset
+            registers.sort_by_key(|register| register.offset);
+            registers.dedup_by_key(|register| register.offset);
+            SimPeripheral {
+                name: peripheral.name.clone(),
+                base_addr: peripheral.base_addr.clone(),
+                registers,
+            }
+        })
+        .collect();
+    info!("Simulator contains {} peripherals", result.len());
+    result
+}
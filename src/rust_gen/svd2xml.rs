@@ -0,0 +1,379 @@
+//! Write svd device description as SVD (CMSIS-SVD 1.3) file.
+//!
+//! Only the elements supported by `svd_rs` are written. Arrays and `derivedFrom` references are
+//! kept, so that parsing the written file gives back the same device description.
+use std::fmt::Display;
+use svd_parser::svd::{
+    self, AddressBlock, BitRangeType, Cpu, DimElement, EnumeratedValue, EnumeratedValues,
+    Interrupt, MaybeArray, RegisterCluster, RegisterProperties, WriteConstraint,
+};
+
+/// Minimal XML writer with indentation of nested elements
+struct XmlWriter {
+    output: String,
+    level: usize,
+}
+
+/// Replace characters that are not allowed in XML text and attribute values
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Format number as `0x` prefixed hexadecimal value
+fn hex(value: impl Into<u64>) -> String {
+    format!("0x{:X}", value.into())
+}
+
+impl XmlWriter {
+    fn new() -> Self {
+        Self {
+            output: String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n"),
+            level: 0,
+        }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.level {
+            self.output.push_str("  ");
+        }
+    }
+
+    /// Open element with attributes. Attributes with `None` value are skipped.
+    fn open(&mut self, tag: &str, attributes: &[(&str, Option<&str>)]) {
+        self.indent();
+        self.output.push('<');
+        self.output.push_str(tag);
+        for (name, value) in attributes {
+            if let Some(value) = value {
+                self.output
+                    .push_str(&format!(" {}=\"{}\"", name, escape(value)));
+            }
+        }
+        self.output.push_str(">\n");
+        self.level += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.level -= 1;
+        self.indent();
+        self.output.push_str(&format!("</{}>\n", tag));
+    }
+
+    /// Write element containing only text
+    fn text(&mut self, tag: &str, value: impl Display) {
+        self.indent();
+        self.output
+            .push_str(&format!("<{tag}>{}</{tag}>\n", escape(&value.to_string())));
+    }
+
+    fn optional_text(&mut self, tag: &str, value: Option<impl Display>) {
+        if let Some(value) = value {
+            self.text(tag, value);
+        }
+    }
+}
+
+fn write_dim_element(writer: &mut XmlWriter, dim: &DimElement) {
+    writer.text("dim", dim.dim);
+    writer.text("dimIncrement", hex(dim.dim_increment));
+    if let Some(dim_index) = &dim.dim_index {
+        writer.text("dimIndex", dim_index.join(","));
+    }
+    writer.optional_text("dimName", dim.dim_name.as_ref());
+    if let Some(dim_array_index) = &dim.dim_array_index {
+        writer.open("dimArrayIndex", &[]);
+        writer.optional_text("headerEnumName", dim_array_index.header_enum_name.as_ref());
+        for value in &dim_array_index.values {
+            write_enumerated_value(writer, value);
+        }
+        writer.close("dimArrayIndex");
+    }
+}
+
+fn write_optional_dim_element<T>(writer: &mut XmlWriter, element: &MaybeArray<T>) {
+    if let MaybeArray::Array(_, dim) = element {
+        write_dim_element(writer, dim);
+    }
+}
+
+fn write_register_properties(writer: &mut XmlWriter, properties: &RegisterProperties) {
+    writer.optional_text("size", properties.size.map(hex));
+    writer.optional_text("access", properties.access.map(|access| access.as_str()));
+    writer.optional_text(
+        "protection",
+        properties.protection.map(|protection| protection.as_str()),
+    );
+    writer.optional_text("resetValue", properties.reset_value.map(hex));
+    writer.optional_text("resetMask", properties.reset_mask.map(hex));
+}
+
+fn write_write_constraint(writer: &mut XmlWriter, write_constraint: &WriteConstraint) {
+    writer.open("writeConstraint", &[]);
+    match write_constraint {
+        WriteConstraint::WriteAsRead(value) => writer.text("writeAsRead", value),
+        WriteConstraint::UseEnumeratedValues(value) => writer.text("useEnumeratedValues", value),
+        WriteConstraint::Range(range) => {
+            writer.open("range", &[]);
+            writer.text("minimum", range.min);
+            writer.text("maximum", range.max);
+            writer.close("range");
+        }
+    }
+    writer.close("writeConstraint");
+}
+
+fn write_enumerated_value(writer: &mut XmlWriter, value: &EnumeratedValue) {
+    writer.open("enumeratedValue", &[]);
+    writer.text("name", &value.name);
+    writer.optional_text("description", value.description.as_ref());
+    writer.optional_text("value", value.value.map(hex));
+    writer.optional_text("isDefault", value.is_default);
+    writer.close("enumeratedValue");
+}
+
+fn write_enumerated_values(writer: &mut XmlWriter, enumerated_values: &EnumeratedValues) {
+    writer.open(
+        "enumeratedValues",
+        &[("derivedFrom", enumerated_values.derived_from.as_deref())],
+    );
+    writer.optional_text("name", enumerated_values.name.as_ref());
+    writer.optional_text("usage", enumerated_values.usage.map(|usage| usage.as_str()));
+    for value in &enumerated_values.values {
+        write_enumerated_value(writer, value);
+    }
+    writer.close("enumeratedValues");
+}
+
+fn write_field(writer: &mut XmlWriter, field: &svd::Field) {
+    writer.open("field", &[("derivedFrom", field.derived_from.as_deref())]);
+    write_optional_dim_element(writer, field);
+    writer.text("name", &field.name);
+    writer.optional_text("description", field.description.as_ref());
+    let bit_range = &field.bit_range;
+    match bit_range.range_type {
+        BitRangeType::BitRange => writer.text("bitRange", bit_range.bit_range()),
+        BitRangeType::OffsetWidth => {
+            writer.text("bitOffset", bit_range.offset);
+            writer.text("bitWidth", bit_range.width);
+        }
+        BitRangeType::MsbLsb => {
+            writer.text("lsb", bit_range.lsb());
+            writer.text("msb", bit_range.msb());
+        }
+    }
+    writer.optional_text("access", field.access.map(|access| access.as_str()));
+    writer.optional_text(
+        "modifiedWriteValues",
+        field.modified_write_values.map(|value| value.as_str()),
+    );
+    if let Some(write_constraint) = &field.write_constraint {
+        write_write_constraint(writer, write_constraint);
+    }
+    writer.optional_text(
+        "readAction",
+        field.read_action.map(|action| action.as_str()),
+    );
+    for enumerated_values in &field.enumerated_values {
+        write_enumerated_values(writer, enumerated_values);
+    }
+    writer.close("field");
+}
+
+fn write_register(writer: &mut XmlWriter, register: &svd::Register) {
+    writer.open(
+        "register",
+        &[("derivedFrom", register.derived_from.as_deref())],
+    );
+    write_optional_dim_element(writer, register);
+    writer.text("name", &register.name);
+    writer.optional_text("displayName", register.display_name.as_ref());
+    writer.optional_text("description", register.description.as_ref());
+    writer.optional_text("alternateGroup", register.alternate_group.as_ref());
+    writer.optional_text("alternateRegister", register.alternate_register.as_ref());
+    writer.text("addressOffset", hex(register.address_offset));
+    write_register_properties(writer, &register.properties);
+    writer.optional_text(
+        "dataType",
+        register.datatype.map(|datatype| datatype.as_str()),
+    );
+    writer.optional_text(
+        "modifiedWriteValues",
+        register.modified_write_values.map(|value| value.as_str()),
+    );
+    if let Some(write_constraint) = &register.write_constraint {
+        write_write_constraint(writer, write_constraint);
+    }
+    writer.optional_text(
+        "readAction",
+        register.read_action.map(|action| action.as_str()),
+    );
+    if let Some(fields) = &register.fields {
+        if !fields.is_empty() {
+            writer.open("fields", &[]);
+            for field in fields {
+                write_field(writer, field);
+            }
+            writer.close("fields");
+        }
+    }
+    writer.close("register");
+}
+
+fn write_cluster(writer: &mut XmlWriter, cluster: &svd::Cluster) {
+    writer.open(
+        "cluster",
+        &[("derivedFrom", cluster.derived_from.as_deref())],
+    );
+    write_optional_dim_element(writer, cluster);
+    writer.text("name", &cluster.name);
+    writer.optional_text("description", cluster.description.as_ref());
+    writer.optional_text("alternateCluster", cluster.alternate_cluster.as_ref());
+    writer.optional_text("headerStructName", cluster.header_struct_name.as_ref());
+    writer.text("addressOffset", hex(cluster.address_offset));
+    write_register_properties(writer, &cluster.default_register_properties);
+    write_register_clusters(writer, &cluster.children);
+    writer.close("cluster");
+}
+
+fn write_register_clusters(writer: &mut XmlWriter, register_clusters: &[RegisterCluster]) {
+    for register_cluster in register_clusters {
+        match register_cluster {
+            RegisterCluster::Register(register) => write_register(writer, register),
+            RegisterCluster::Cluster(cluster) => write_cluster(writer, cluster),
+        }
+    }
+}
+
+fn write_address_block(writer: &mut XmlWriter, address_block: &AddressBlock) {
+    writer.open("addressBlock", &[]);
+    writer.text("offset", hex(address_block.offset));
+    writer.text("size", hex(address_block.size));
+    writer.text("usage", address_block.usage.as_str());
+    writer.optional_text(
+        "protection",
+        address_block
+            .protection
+            .map(|protection| protection.as_str()),
+    );
+    writer.close("addressBlock");
+}
+
+fn write_interrupt(writer: &mut XmlWriter, interrupt: &Interrupt) {
+    writer.open("interrupt", &[]);
+    writer.text("name", &interrupt.name);
+    writer.optional_text("description", interrupt.description.as_ref());
+    writer.text("value", interrupt.value);
+    writer.close("interrupt");
+}
+
+fn write_peripheral(writer: &mut XmlWriter, peripheral: &svd::Peripheral) {
+    writer.open(
+        "peripheral",
+        &[("derivedFrom", peripheral.derived_from.as_deref())],
+    );
+    write_optional_dim_element(writer, peripheral);
+    writer.text("name", &peripheral.name);
+    writer.optional_text("displayName", peripheral.display_name.as_ref());
+    writer.optional_text("version", peripheral.version.as_ref());
+    writer.optional_text("description", peripheral.description.as_ref());
+    writer.optional_text(
+        "alternatePeripheral",
+        peripheral.alternate_peripheral.as_ref(),
+    );
+    writer.optional_text("groupName", peripheral.group_name.as_ref());
+    writer.optional_text("prependToName", peripheral.prepend_to_name.as_ref());
+    writer.optional_text("appendToName", peripheral.append_to_name.as_ref());
+    writer.optional_text("headerStructName", peripheral.header_struct_name.as_ref());
+    writer.text("baseAddress", hex(peripheral.base_address));
+    write_register_properties(writer, &peripheral.default_register_properties);
+    for address_block in peripheral.address_block.iter().flatten() {
+        write_address_block(writer, address_block);
+    }
+    for interrupt in &peripheral.interrupt {
+        write_interrupt(writer, interrupt);
+    }
+    if let Some(registers) = &peripheral.registers {
+        if !registers.is_empty() {
+            writer.open("registers", &[]);
+            write_register_clusters(writer, registers);
+            writer.close("registers");
+        }
+    }
+    writer.close("peripheral");
+}
+
+fn write_cpu(writer: &mut XmlWriter, cpu: &Cpu) {
+    writer.open("cpu", &[]);
+    writer.text("name", &cpu.name);
+    writer.text("revision", &cpu.revision);
+    writer.text("endian", cpu.endian.as_str());
+    writer.text("mpuPresent", cpu.mpu_present);
+    writer.text("fpuPresent", cpu.fpu_present);
+    writer.optional_text("fpuDP", cpu.fpu_double_precision);
+    writer.optional_text("dspPresent", cpu.dsp_present);
+    writer.optional_text("icachePresent", cpu.icache_present);
+    writer.optional_text("dcachePresent", cpu.dcache_present);
+    writer.optional_text("itcmPresent", cpu.itcm_present);
+    writer.optional_text("dtcmPresent", cpu.dtcm_present);
+    writer.optional_text("vtorPresent", cpu.vtor_present);
+    writer.text("nvicPrioBits", cpu.nvic_priority_bits);
+    writer.text("vendorSystickConfig", cpu.has_vendor_systick);
+    writer.optional_text("deviceNumInterrupts", cpu.device_num_interrupts);
+    writer.optional_text("sauNumRegions", cpu.sau_num_regions);
+    writer.close("cpu");
+}
+
+/// Encode device as SVD file content
+pub(super) fn encode(device: &svd::Device) -> String {
+    let mut writer = XmlWriter::new();
+    writer.open(
+        "device",
+        &[
+            ("schemaVersion", Some(&device.schema_version)),
+            ("xmlns:xs", Some(&device.xmlns_xs)),
+            (
+                "xs:noNamespaceSchemaLocation",
+                Some(&device.no_namespace_schema_location),
+            ),
+        ],
+    );
+    writer.optional_text("vendor", device.vendor.as_ref());
+    writer.optional_text("vendorID", device.vendor_id.as_ref());
+    writer.text("name", &device.name);
+    writer.optional_text("series", device.series.as_ref());
+    writer.text("version", &device.version);
+    writer.text("description", &device.description);
+    writer.optional_text("licenseText", device.license_text.as_ref());
+    if let Some(cpu) = &device.cpu {
+        write_cpu(&mut writer, cpu);
+    }
+    writer.optional_text(
+        "headerSystemFilename",
+        device.header_system_filename.as_ref(),
+    );
+    writer.optional_text(
+        "headerDefinitionsPrefix",
+        device.header_definitions_prefix.as_ref(),
+    );
+    writer.text("addressUnitBits", device.address_unit_bits);
+    writer.text("width", device.width);
+    write_register_properties(&mut writer, &device.default_register_properties);
+    writer.open("peripherals", &[]);
+    for peripheral in &device.peripherals {
+        write_peripheral(&mut writer, peripheral);
+    }
+    writer.close("peripherals");
+    writer.close("device");
+    writer.output
+}
@@ -0,0 +1,149 @@
+//! Split of peripheral modules in crates of a cargo workspace.
+//!
+//! Each peripheral module is generated in its own crate, peripherals of a feature group of
+//! configuration file share a crate. Derived peripherals are in the crate of their module,
+//! modules using structs of other modules are in the crate of these modules.
+//! The main crate re-exports modules, structs and instances of peripherals.
+use super::ir::*;
+use super::util::ToSanitizedSymbol;
+use anyhow::{anyhow, Result};
+use linked_hash_map::LinkedHashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+/// Get crates of workspace in order of first peripheral of each crate
+pub fn get_workspace_crates(
+    device: &Device,
+    package_name: &str,
+    feature_groups: &BTreeMap<String, Vec<String>>,
+    offset_only: bool,
+) -> Result<Vec<WorkspaceCrate>> {
+    // Crate of each peripheral module
+    let mut module_keys: BTreeMap<String, String> = BTreeMap::new();
+    for peri in device.peripheral_mod.values() {
+        let peri = peri.borrow();
+        if module_keys.contains_key(&peri.module_id) {
+            continue;
+        }
+        // Peripheral that defines the module
+        let owner = device
+            .peripheral_mod
+            .values()
+            .map(|other| other.borrow())
+            .find(|other| !other.is_derived_from && other.module_id == peri.module_id)
+            .map_or_else(|| peri.name.clone(), |other| other.name.clone());
+        let owner_feature = owner.to_sanitized_mod_ident();
+        let groups: Vec<&String> = feature_groups
+            .iter()
+            .filter(|(_, features)| features.contains(&owner_feature))
+            .map(|(group, _)| group)
+            .collect();
+        let key = match groups.as_slice() {
+            [] => peri.module_id.clone(),
+            [group] => (*group).clone(),
+            _ => {
+                return Err(anyhow!(
+                    "Peripheral {owner} is in more than one feature group, crates of workspace require disjoint groups"
+                ))
+            }
+        };
+        module_keys.insert(peri.module_id.clone(), key);
+    }
+    // Modules with registers or clusters derived from other modules are in the crate of these modules
+    let references: Vec<(String, String)> = device
+        .peripheral_mod
+        .values()
+        .flat_map(|peri| {
+            let peri = peri.borrow();
+            referenced_modules(&peri)
+                .into_iter()
+                .map(|module| (peri.module_id.clone(), module))
+                .collect::<Vec<_>>()
+        })
+        .filter(|(_, module)| module_keys.contains_key(module))
+        .collect();
+    let mut merged = true;
+    while merged {
+        merged = false;
+        for (module, referenced) in &references {
+            let (key, referenced_key) = (&module_keys[module], &module_keys[referenced]);
+            if key != referenced_key {
+                let (key, referenced_key) = (key.clone(), referenced_key.clone());
+                for value in module_keys.values_mut().filter(|value| **value == key) {
+                    value.clone_from(&referenced_key);
+                }
+                merged = true;
+            }
+        }
+    }
+
+    let mut crates: Vec<WorkspaceCrate> = Vec::new();
+    for (name, peri) in &device.peripheral_mod {
+        let peri = peri.borrow();
+        let key = &module_keys[&peri.module_id];
+        let package = format!("{package_name}-{key}");
+        let index = match crates.iter().position(|c| c.package == package) {
+            Some(index) => index,
+            None => {
+                crates.push(WorkspaceCrate {
+                    ident: package.replace('-', "_"),
+                    package,
+                    path: format!("peripherals/{key}"),
+                    modules: Vec::new(),
+                    exports: Vec::new(),
+                });
+                crates.len() - 1
+            }
+        };
+        let member = &mut crates[index];
+        if !member.modules.contains(&peri.module_id) {
+            member.modules.push(peri.module_id.clone());
+        }
+
+        let feature = peri.name.to_sanitized_mod_ident();
+        let mut items = Vec::new();
+        if !peri.is_derived_from {
+            items.push(feature.clone());
+            items.push(peri.type_name.to_sanitized_struct_ident());
+        }
+        if !offset_only {
+            let instance = name.to_uppercase();
+            if !peri.secure_base_addr.is_empty() {
+                items.push(format!("{instance}_S"));
+            }
+            if !peri.non_secure_base_addr.is_empty() {
+                items.push(format!("{instance}_NS"));
+            }
+            items.push(instance);
+        }
+        member.exports.push(WorkspaceExport {
+            parent: peri.is_derived_from.then(|| peri.module_id.clone()),
+            feature,
+            items,
+        });
+    }
+    Ok(crates)
+}
+
+/// Modules of structs used by registers and clusters of peripheral other than its own module
+fn referenced_modules(peri: &PeripheralMod) -> BTreeSet<String> {
+    fn visit(
+        registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+        clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+        result: &mut BTreeSet<String>,
+    ) {
+        for register in registers.values() {
+            result.extend(register.borrow().struct_module_path.first().cloned());
+        }
+        for cluster in clusters.values() {
+            let cluster = cluster.borrow();
+            result.extend(cluster.struct_module_path.first().cloned());
+            visit(&cluster.registers, &cluster.clusters, result);
+        }
+    }
+    let mut result = BTreeSet::new();
+    visit(&peri.registers, &peri.clusters, &mut result);
+    result.remove(&peri.module_id);
+    result
+}
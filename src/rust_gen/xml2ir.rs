@@ -1,15 +1,16 @@
 mod svd2temp;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::rc::Rc;
 
 use super::ir::*;
 use super::util::*;
 use crate::svd_util::*;
-use crate::SvdValidationLevel;
-use anyhow::Result;
+use crate::{AddressBlockCheck, AliasedRegisterCheck, SvdValidationLevel};
+use anyhow::{anyhow, Result};
 use linked_hash_map::LinkedHashMap;
-use log::{debug, error, warn};
+use log::{debug, error, info, warn};
 use svd2temp::*;
 use svd_parser::svd;
 
@@ -70,6 +71,27 @@ fn get_dim_dim_increment<T>(array: &svd::array::MaybeArray<T>) -> (u32, u32) {
     }
 }
 
+/// Get index of each element of an svd array
+///
+/// # Arguments
+///
+/// * `array` any MaybeArray type
+///
+/// # Result
+///
+/// Indexes declared by `dimIndex` or position of elements if `dimIndex` is missing. Empty if `array`=Single()
+fn get_dim_index<T>(array: &svd::array::MaybeArray<T>) -> Vec<String> {
+    match array {
+        svd::array::MaybeArray::Single(_) => Vec::new(),
+        svd::array::MaybeArray::Array(_, dim_element) => match dim_element.dim_index {
+            Some(ref indexes) => indexes.clone(),
+            None => (0..dim_element.dim)
+                .map(|index| index.to_string())
+                .collect(),
+        },
+    }
+}
+
 #[derive(Debug)]
 enum DeviceItem {
     Register(Rc<RefCell<Register>>),
@@ -132,6 +154,10 @@ impl Visitor {
 
             peripheral.is_derived_from = derived_peripheral
                 .is_some_and(|derived_peri| peripheral.has_same_type(&derived_peri));
+            // type_name of derived peripheral is cloned from the parent
+            if !peripheral.is_derived_from {
+                peripheral.type_name.clone_from(&name);
+            }
             let peripheral_mod = Rc::new(RefCell::new(peripheral));
             self.device
                 .peripheral_mod
@@ -165,6 +191,15 @@ impl Visitor {
         peripheral.base_addr = (0..dim)
             .map(|index| svd_peripheral.base_address + (index * dim_increment) as u64)
             .collect();
+        peripheral.address_blocks = svd_peripheral
+            .address_block
+            .iter()
+            .flatten()
+            .map(|block| AddressBlock {
+                offset: block.offset,
+                size: block.size,
+            })
+            .collect();
         peripheral.interrupts = svd_peripheral
             .interrupt
             .iter()
@@ -196,6 +231,7 @@ impl Visitor {
         register.description = reg.description.clone().unwrap_or_default();
         register.offset = reg.address_offset;
         (register.dim, register.dim_increment) = get_dim_dim_increment(reg);
+        register.dim_index = get_dim_index(reg);
 
         if register.struct_id.is_empty() {
             register.struct_module_path = Vec::with_capacity(10);
@@ -204,8 +240,17 @@ impl Visitor {
             );
             register.struct_id = register.name.to_sanitized_struct_ident();
         }
+        let is_register_volatile =
+            has_side_effects(reg.modified_write_values, reg.read_action.as_ref());
+        // Derived registers without size have the size of their parent
+        let register_bits = reg
+            .properties
+            .size
+            .unwrap_or_else(|| register.size.byte_size() as u32 * 8);
         // Get fields
         let mut fields = Vec::new();
+        let mut verify_mask = 0u64;
+        let mut fields_mask = 0u64;
         for field in reg.fields() {
             assert!(
                 field.derived_from.is_none(),
@@ -215,27 +260,55 @@ impl Visitor {
             let offset = field.bit_range.offset;
             let mask = (0..field.bit_range.width - 1).fold(0x1u32, |acc, _| (acc << 1) | 0x1);
             let name = field.name.to_internal_ident();
-            let svd_field_access = match field.access {
-                None => {
-                    error!("Inheritance of access is not supported. Bitfield: {} access shall be specified. Bitfield skipped",name);
+            // Access of field has priority over the one of register. A read-write register
+            // can contain read-only or write-only fields. If the field doesn't specify
+            // any access, it is inherited from the register.
+            let access = match (field.access, reg.properties.access) {
+                (Some(field_access), _) => get_bitfield_access(field_access),
+                (None, Some(reg_access)) => get_bitfield_access(reg_access),
+                (None, None) if reg.derived_from.is_some() => match register.access {
+                    RegisterAccess::R => RegisterBitfieldAccess::R,
+                    RegisterAccess::W => RegisterBitfieldAccess::W,
+                    RegisterAccess::RW => RegisterBitfieldAccess::RW,
+                },
+                (None, None) => {
+                    error!("Neither bitfield: {} nor its register specify an access mode. Bitfield skipped",name);
                     continue;
                 }
-                Some(acc) => acc,
-            };
-            let access = match svd_field_access {
-                svd::Access::ReadOnly => RegisterBitfieldAccess::R,
-                svd::Access::WriteOnly => RegisterBitfieldAccess::W,
-                svd::Access::ReadWrite => RegisterBitfieldAccess::RW,
-                svd::Access::WriteOnce => RegisterBitfieldAccess::W,
-                svd::Access::ReadWriteOnce => RegisterBitfieldAccess::RW,
             };
             let enum_type = get_values_types(field);
             let (dim, dim_increment) = get_dim_dim_increment(field);
+            // Elements of bitfield arrays are placed at offset + index * dim_increment,
+            // gaps between elements are allowed but overlapping elements are not.
+            if dim > 1 && dim_increment < field.bit_range.width {
+                error!("Elements of bitfield array {} in register {} overlap: dimIncrement {} is smaller than bitfield width {}. Bitfield skipped", name, register.name, dim_increment, field.bit_range.width);
+                continue;
+            }
+            // Last element of bitfield array shall be inside the register
+            let end_bit = offset as u64
+                + (dim as u64).saturating_sub(1) * dim_increment as u64
+                + field.bit_range.width as u64;
+            if end_bit > register_bits as u64 {
+                error!("Bitfield {} in register {} ends at bit {} outside of register of {} bits. Bitfield skipped", name, register.name, end_bit, register_bits);
+                continue;
+            }
+            for index in 0..dim {
+                fields_mask |= (mask as u64) << (offset + index * dim_increment);
+            }
+            if access == RegisterBitfieldAccess::RW
+                && !is_register_volatile
+                && !has_side_effects(field.modified_write_values, field.read_action.as_ref())
+            {
+                for index in 0..dim {
+                    verify_mask |= (mask as u64) << (offset + index * dim_increment);
+                }
+            }
             fields.push(FieldGetterSetter {
                 name,
                 description,
                 offset,
                 mask,
+                width: field.bit_range.width,
                 enum_type,
                 access,
                 size: BitSize::val_2_bit_size(mask.into()),
@@ -270,6 +343,9 @@ impl Visitor {
             ),
         }
 
+        register.is_alternate = reg.alternate_register.is_some() || reg.alternate_group.is_some();
+        register.has_read_side_effects =
+            reg.read_action.is_some() || reg.fields().any(|field| field.read_action.is_some());
         register.has_enumerated_fields = fields.iter().any(|f| f.enum_type.is_some());
 
         match reg.properties.access {
@@ -310,6 +386,16 @@ impl Visitor {
                 }
             }
         };
+        register.verify_mask = if reg.fields().next().is_none() {
+            if register.access == RegisterAccess::RW && !is_register_volatile {
+                u64::MAX >> (64 - 8 * register.size.byte_size())
+            } else {
+                0
+            }
+        } else {
+            verify_mask
+        };
+        register.fields_mask = fields_mask;
         register.fields = fields
             .into_iter()
             .map(|f| (f.name.clone(), Rc::new(RefCell::new(f))))
@@ -321,8 +407,9 @@ impl Visitor {
         cluster.description = cluster_svd.description.clone().unwrap_or_default();
         cluster.offset = cluster_svd.address_offset;
         (cluster.dim, cluster.dim_increment) = get_dim_dim_increment(cluster_svd);
+        cluster.dim_index = get_dim_index(cluster_svd);
 
-        if let Some(header_struct_name) = &cluster_svd.header_struct_name {
+        if let Some(header_struct_name) = &cluster_svd.header_struct_name() {
             cluster.struct_module_path = Vec::with_capacity(10);
             cluster.struct_module_path.extend_from_slice(
                 &self.current_mod_ir_path[0..self.current_mod_ir_path.len() - 1],
@@ -474,6 +561,17 @@ impl Visitor {
     }
 }
 
+/// Convert svd access of a bitfield to the access used in generated code
+fn get_bitfield_access(access: svd::Access) -> RegisterBitfieldAccess {
+    match access {
+        svd::Access::ReadOnly => RegisterBitfieldAccess::R,
+        svd::Access::WriteOnly => RegisterBitfieldAccess::W,
+        svd::Access::ReadWrite => RegisterBitfieldAccess::RW,
+        svd::Access::WriteOnce => RegisterBitfieldAccess::W,
+        svd::Access::ReadWriteOnce => RegisterBitfieldAccess::RW,
+    }
+}
+
 fn get_values_types(field: &svd::Field) -> Option<EnumeratedValueType> {
     if field.enumerated_values.is_empty() {
         return None;
@@ -516,6 +614,15 @@ fn get_values_types(field: &svd::Field) -> Option<EnumeratedValueType> {
     })
 }
 
+/// Convert validation level of command line to the one of svd_rs module
+pub(super) fn get_validate_level(svd_validation_level: SvdValidationLevel) -> svd::ValidateLevel {
+    match svd_validation_level {
+        SvdValidationLevel::Disabled => svd::ValidateLevel::Disabled,
+        SvdValidationLevel::Weak => svd::ValidateLevel::Weak,
+        SvdValidationLevel::Strict => svd::ValidateLevel::Strict,
+    }
+}
+
 /// Parse xml and trasform to device description of svd_rs module
 ///
 /// # Arguments
@@ -532,11 +639,7 @@ pub(super) fn parse_xml(
     let mut parser_config = svd_parser::Config::default();
     parser_config.expand_properties = true;
     parser_config.ignore_enums = false;
-    parser_config.validate_level = match svd_validation_level {
-        SvdValidationLevel::Disabled => svd::ValidateLevel::Disabled,
-        SvdValidationLevel::Weak => svd::ValidateLevel::Weak,
-        SvdValidationLevel::Strict => svd::ValidateLevel::Strict,
-    };
+    parser_config.validate_level = get_validate_level(svd_validation_level);
     let result = svd_parser::parse_with_config(xml, &parser_config);
     if let Err(err) = &result {
         if let Some(error_at) = err.downcast_ref::<svd_parser::SVDErrorAt>() {
@@ -546,6 +649,176 @@ pub(super) fn parse_xml(
     result
 }
 
+/// Collect name and offset of all registers that are not completely inside one of `address_blocks`
+///
+/// # Arguments
+///
+/// * `base_offset` offset of the cluster containing `registers` and `clusters` relative to peripheral base address
+fn collect_registers_outside_address_blocks(
+    address_blocks: &[AddressBlock],
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    base_offset: u64,
+    result: &mut Vec<(String, u64)>,
+) {
+    for register in registers.values() {
+        let register = register.borrow();
+        for index in 0..register.dim {
+            let offset =
+                base_offset + register.offset as u64 + (index * register.dim_increment) as u64;
+            let end = offset + register.size.byte_size();
+            if !address_blocks.iter().any(|block| {
+                offset >= block.offset as u64 && end <= block.offset as u64 + block.size as u64
+            }) {
+                result.push((register.name.clone(), offset));
+            }
+        }
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        for index in 0..cluster.dim {
+            collect_registers_outside_address_blocks(
+                address_blocks,
+                &cluster.registers,
+                &cluster.clusters,
+                base_offset + cluster.offset as u64 + (index * cluster.dim_increment) as u64,
+                result,
+            );
+        }
+    }
+}
+
+/// Byte ranges occupied by all elements of a register
+fn register_ranges(register: &Register) -> impl Iterator<Item = (u64, u64)> + '_ {
+    (0..register.dim).map(move |index| {
+        let offset = register.offset as u64 + (index * register.dim_increment) as u64;
+        (offset, offset + register.size.byte_size())
+    })
+}
+
+/// Find registers of the same peripheral or cluster that share an address without being declared
+/// as alternate registers. Names of aliased registers are stored in each register to document them.
+fn check_aliased_registers(
+    peripheral_name: &str,
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    aliased_register_check: AliasedRegisterCheck,
+) -> Result<()> {
+    let registers: Vec<_> = registers.values().collect();
+    let mut aliased_pairs = Vec::new();
+    for (index, register) in registers.iter().enumerate() {
+        let register = register.borrow();
+        if register.is_alternate {
+            continue;
+        }
+        for other in &registers[index + 1..] {
+            let other = other.borrow();
+            if other.is_alternate {
+                continue;
+            }
+            let overlap = register_ranges(&register).any(|(start, end)| {
+                register_ranges(&other)
+                    .any(|(other_start, other_end)| start < other_end && other_start < end)
+            });
+            if overlap {
+                aliased_pairs.push((register.name.clone(), other.name.clone()));
+            }
+        }
+    }
+    for (name, other_name) in &aliased_pairs {
+        match aliased_register_check {
+            AliasedRegisterCheck::Error => error!(
+                "Registers {} and {} of peripheral {} share the same address and they are not alternate registers",
+                name, other_name, peripheral_name
+            ),
+            AliasedRegisterCheck::Warning => warn!(
+                "Registers {} and {} of peripheral {} share the same address and they are not alternate registers",
+                name, other_name, peripheral_name
+            ),
+            AliasedRegisterCheck::Relaxed => info!(
+                "Registers {} and {} of peripheral {} share the same address",
+                name, other_name, peripheral_name
+            ),
+        }
+        // Registers of derived peripherals are shared with their parent
+        for register in &registers {
+            let mut register = register.borrow_mut();
+            let alias = if register.name == *name {
+                other_name
+            } else if register.name == *other_name {
+                name
+            } else {
+                continue;
+            };
+            if !register.aliases.contains(alias) {
+                register.aliases.push(alias.clone());
+            }
+        }
+    }
+    if aliased_register_check == AliasedRegisterCheck::Error && !aliased_pairs.is_empty() {
+        return Err(anyhow!(
+            "{} pairs of registers of peripheral {} share the same address",
+            aliased_pairs.len(),
+            peripheral_name
+        ));
+    }
+    for cluster in clusters.values() {
+        let cluster = cluster.borrow();
+        check_aliased_registers(
+            peripheral_name,
+            &cluster.registers,
+            &cluster.clusters,
+            aliased_register_check,
+        )?;
+    }
+    Ok(())
+}
+
+/// Verify that every register of peripheral falls inside one of declared address blocks
+fn check_address_blocks(
+    peripheral: &PeripheralMod,
+    address_block_check: AddressBlockCheck,
+) -> Result<()> {
+    if address_block_check == AddressBlockCheck::Disabled {
+        return Ok(());
+    }
+    if peripheral.address_blocks.is_empty() {
+        debug!(
+            "No address block defined for peripheral {}. Register offsets not checked",
+            peripheral.name
+        );
+        return Ok(());
+    }
+    let mut outside_registers = Vec::new();
+    collect_registers_outside_address_blocks(
+        &peripheral.address_blocks,
+        &peripheral.registers,
+        &peripheral.clusters,
+        0,
+        &mut outside_registers,
+    );
+    for (register_name, offset) in &outside_registers {
+        match address_block_check {
+            AddressBlockCheck::Error => error!(
+                "Register {} at offset 0x{:x} is outside of address blocks of peripheral {}",
+                register_name, offset, peripheral.name
+            ),
+            _ => warn!(
+                "Register {} at offset 0x{:x} is outside of address blocks of peripheral {}",
+                register_name, offset, peripheral.name
+            ),
+        }
+    }
+    if address_block_check == AddressBlockCheck::Error && !outside_registers.is_empty() {
+        return Err(anyhow!(
+            "{} registers of peripheral {} are outside of declared address blocks",
+            outside_registers.len(),
+            peripheral.name
+        ));
+    }
+    Ok(())
+}
+
 /// Generate interrupt table including holes that will be used to create required function for cortex-m-rt
 fn get_interrupt_table(
     peripheral_types: &LinkedHashMap<String, Rc<RefCell<PeripheralMod>>>,
@@ -582,9 +855,272 @@ fn get_interrupt_table(
     }
 }
 
+/// Settings to generate secure and non-secure instances of peripherals for ARMv8-M TrustZone devices
+pub(super) struct TrustZone {
+    /// Address bit that selects the secure alias
+    pub alias_bit: u8,
+    /// Address ranges of non-secure SAU regions. `None` if SAU regions are not defined.
+    pub non_secure_regions: Option<Vec<RangeInclusive<u64>>>,
+}
+
+/// Parse decimal or `0x` prefixed hexadecimal number
+fn parse_sau_address(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let result = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    result.map_err(|_| anyhow!("Invalid address {text} in SAU region"))
+}
+
+/// Get address ranges of enabled non-secure SAU regions defined in `sauRegionsConfig` of SVD.
+///
+/// `sauRegionsConfig` is not supported by svd_rs, so it is read directly from xml.
+pub(super) fn get_non_secure_sau_regions(xml: &str) -> Result<Option<Vec<RangeInclusive<u64>>>> {
+    let document = roxmltree::Document::parse(xml)?;
+    let Some(sau_regions_config) = document
+        .descendants()
+        .find(|node| node.has_tag_name("sauRegionsConfig"))
+    else {
+        return Ok(None);
+    };
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .map(|text| text.trim().to_owned())
+            .ok_or_else(|| anyhow!("Missing {name} in SAU region"))
+    };
+    let mut regions = Vec::new();
+    let mut non_secure_regions = Vec::new();
+    for region in sau_regions_config
+        .children()
+        .filter(|node| node.has_tag_name("region"))
+        .filter(|node| node.attribute("enabled") != Some("false"))
+    {
+        let base = parse_sau_address(&child_text(region, "base")?)?;
+        let limit = parse_sau_address(&child_text(region, "limit")?)?;
+        regions.push(base..=limit);
+        if child_text(region, "access")? == "n" {
+            non_secure_regions.push(base..=limit);
+        }
+    }
+    Ok((!regions.is_empty()).then_some(non_secure_regions))
+}
+
+/// Check if address is inside a non-secure region of SAU.
+/// If SAU regions are not defined in register description all addresses are considered non-secure accessible.
+fn is_non_secure_region(trustzone: &TrustZone, address: u64) -> bool {
+    match trustzone.non_secure_regions {
+        None => true,
+        Some(ref regions) => regions.iter().any(|region| region.contains(&address)),
+    }
+}
+
+/// Add secure and non-secure alias of all peripherals.
+///
+/// Secure alias has bit `alias_bit` of the address set, non-secure alias has it cleared.
+/// Non-secure alias is generated only if it is inside a non-secure SAU region.
+fn add_trustzone_aliases(device: &Device, trustzone: &TrustZone) {
+    let alias_mask = 1u64 << trustzone.alias_bit;
+    for peripheral in device.peripheral_mod.values() {
+        let mut peripheral = peripheral.borrow_mut();
+        peripheral.secure_base_addr = peripheral
+            .base_addr
+            .iter()
+            .map(|addr| addr | alias_mask)
+            .collect();
+        let non_secure_base_addr: Vec<u64> = peripheral
+            .base_addr
+            .iter()
+            .map(|addr| addr & !alias_mask)
+            .collect();
+        if non_secure_base_addr
+            .iter()
+            .all(|addr| is_non_secure_region(trustzone, *addr))
+        {
+            peripheral.non_secure_base_addr = non_secure_base_addr;
+        } else {
+            warn!(
+                "Peripheral {} is not in a non-secure SAU region. Non-secure alias not generated",
+                peripheral.name
+            );
+        }
+    }
+}
+
+/// Check if value read from register or bitfield may differ from the value written
+/// because write or read operation has side effects.
+fn has_side_effects(
+    modified_write_values: Option<svd::ModifiedWriteValues>,
+    read_action: Option<&svd::ReadAction>,
+) -> bool {
+    !matches!(
+        modified_write_values,
+        None | Some(svd::ModifiedWriteValues::Modify)
+    ) || read_action.is_some()
+}
+
+/// Start address of bit-band regions (SRAM and peripherals) of Cortex-M3 and Cortex-M4
+const BIT_BAND_REGIONS: [u64; 2] = [0x2000_0000, 0x4000_0000];
+/// Size of bit-band regions
+const BIT_BAND_REGION_SIZE: u64 = 0x10_0000;
+
+/// Check if cpu of device implements bit-banding.
+/// Only Cortex-M3 and Cortex-M4 (and their secure core variant) have bit-band regions.
+fn has_bit_band(svd_device: &svd::Device) -> bool {
+    svd_device
+        .cpu
+        .as_ref()
+        .is_some_and(|cpu| matches!(cpu.name.to_uppercase().as_str(), "CM3" | "CM4" | "SC300"))
+}
+
+/// Check if base addresses of all instances of peripheral are inside a bit-band region
+fn is_in_bit_band_region(peripheral: &PeripheralMod) -> bool {
+    peripheral.base_addr.iter().all(|base_addr| {
+        BIT_BAND_REGIONS
+            .iter()
+            .any(|region| (*region..region + BIT_BAND_REGION_SIZE).contains(base_addr))
+    })
+}
+
+/// Mark peripherals that are inside a bit-band region.
+///
+/// Derived peripherals share the register types of their module,
+/// therefore a module is marked only if all peripherals using it are inside a bit-band region.
+fn mark_bit_band_peripherals(device: &Device) {
+    let mut module_in_region: HashMap<String, bool> = HashMap::new();
+    for peripheral in device.peripheral_mod.values() {
+        let peripheral = peripheral.borrow();
+        let in_region = is_in_bit_band_region(&peripheral);
+        module_in_region
+            .entry(peripheral.module_id.clone())
+            .and_modify(|value| *value &= in_region)
+            .or_insert(in_region);
+    }
+    for peripheral in device.peripheral_mod.values() {
+        let mut peripheral = peripheral.borrow_mut();
+        peripheral.bit_band = module_in_region[&peripheral.module_id];
+    }
+}
+
+/// Keys of serialized IR that don't change the generated struct or refer to its location
+const NON_LAYOUT_KEYS: [&str; 6] = [
+    "description",
+    "struct_module_path",
+    "struct_id",
+    "module_id",
+    "is_derived_from",
+    "is_shared",
+];
+
+/// Remove recursively from serialized IR all keys that don't change the generated struct
+fn remove_non_layout_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for key in NON_LAYOUT_KEYS {
+                map.remove(key);
+            }
+            map.values_mut().for_each(remove_non_layout_keys);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_non_layout_keys),
+        _ => {}
+    }
+}
+
+/// Get layout of registers and clusters of a peripheral or cluster.
+/// Descriptions and location of generated structs are ignored.
+fn get_layout(
+    registers: &LinkedHashMap<String, Rc<RefCell<Register>>>,
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+) -> serde_json::Value {
+    let mut layout = serde_json::json!({ "registers": registers, "clusters": clusters });
+    remove_non_layout_keys(&mut layout);
+    layout
+}
+
+/// Reuse struct of first cluster with same layout in the same peripheral.
+/// Clusters of different peripherals are not shared because peripheral modules are enabled by separate features.
+fn dedup_cluster_layouts(
+    clusters: &LinkedHashMap<String, Rc<RefCell<Cluster>>>,
+    layouts: &mut Vec<(serde_json::Value, Rc<RefCell<Cluster>>)>,
+) {
+    for cluster_rc in clusters.values() {
+        let mut cluster = cluster_rc.borrow_mut();
+        if cluster.is_derived_from {
+            continue;
+        }
+        let layout = get_layout(&cluster.registers, &cluster.clusters);
+        if let Some((_, other)) = layouts
+            .iter()
+            .find(|(other_layout, _)| *other_layout == layout)
+        {
+            let mut other = other.borrow_mut();
+            debug!("Cluster {} shares struct of {}", cluster.name, other.name);
+            cluster
+                .struct_module_path
+                .clone_from(&other.struct_module_path);
+            cluster.struct_id.clone_from(&other.struct_id);
+            cluster.is_derived_from = true;
+            other.is_shared = true;
+            continue;
+        }
+        dedup_cluster_layouts(&cluster.clusters, layouts);
+        drop(cluster);
+        layouts.push((layout, cluster_rc.clone()));
+    }
+}
+
+/// Use module and struct of `other` peripheral for `peripheral`
+fn share_peripheral_module(peripheral: &mut PeripheralMod, other: &PeripheralMod) {
+    info!(
+        "Peripheral {} shares module {}",
+        peripheral.name, other.module_id
+    );
+    peripheral.module_id.clone_from(&other.module_id);
+    peripheral.struct_id.clone_from(&other.struct_id);
+    peripheral.type_name.clone_from(&other.type_name);
+    peripheral.registers = other.registers.clone();
+    peripheral.clusters = other.clusters.clone();
+    peripheral.is_derived_from = true;
+}
+
+/// Detect peripherals and clusters with identical layout and generate their structs only once.
+/// Peripherals with same layout of a previous one are handled as if they were derived from it.
+fn dedup_layouts(device: &Device) {
+    let mut peripheral_layouts: Vec<(serde_json::Value, Rc<RefCell<PeripheralMod>>)> = Vec::new();
+    // Peripherals that share the module of another one indexed by their original module id
+    let mut shared_modules: HashMap<String, Rc<RefCell<PeripheralMod>>> = HashMap::new();
+    for peripheral_rc in device.peripheral_mod.values() {
+        let mut peripheral = peripheral_rc.borrow_mut();
+        if peripheral.is_derived_from {
+            // Peripheral derived from a peripheral whose module is shared
+            if let Some(other) = shared_modules.get(&peripheral.module_id) {
+                share_peripheral_module(&mut peripheral, &other.borrow());
+            }
+            continue;
+        }
+        let layout = get_layout(&peripheral.registers, &peripheral.clusters);
+        if let Some((_, other)) = peripheral_layouts
+            .iter()
+            .find(|(other_layout, _)| *other_layout == layout)
+        {
+            shared_modules.insert(peripheral.module_id.clone(), other.clone());
+            share_peripheral_module(&mut peripheral, &other.borrow());
+            continue;
+        }
+        dedup_cluster_layouts(&peripheral.clusters, &mut Vec::new());
+        peripheral_layouts.push((layout, peripheral_rc.clone()));
+    }
+}
+
 pub(super) fn svd_device2ir(
     svd_device: &svd::Device,
     custom_license_text: &Option<String>,
+    address_block_check: AddressBlockCheck,
+    aliased_register_check: AliasedRegisterCheck,
+    trustzone: Option<&TrustZone>,
+    dedup_layouts: bool,
 ) -> Result<IR> {
     let entity_db = get_entity_db(svd_device);
     // Use custom license if available otherwise use license in svd and if it not present use empty string.
@@ -603,6 +1139,25 @@ pub(super) fn svd_device2ir(
     let mut visitor = Visitor::default();
     visitor.visit_device(svd_device);
     let device = visitor.device;
+    for peripheral in device.peripheral_mod.values() {
+        check_address_blocks(&peripheral.borrow(), address_block_check)?;
+        let peripheral = peripheral.borrow();
+        check_aliased_registers(
+            &peripheral.name,
+            &peripheral.registers,
+            &peripheral.clusters,
+            aliased_register_check,
+        )?;
+    }
+    if let Some(trustzone) = trustzone {
+        add_trustzone_aliases(&device, trustzone);
+    }
+    if dedup_layouts {
+        self::dedup_layouts(&device);
+    }
+    if has_bit_band(svd_device) {
+        mark_bit_band_peripherals(&device);
+    }
     let interrupt_table = get_interrupt_table(&device.peripheral_mod);
     Ok(IR {
         device,
@@ -0,0 +1,121 @@
+//! Removal of redundant peripheral name prefix from names of registers and clusters.
+//!
+//! Many register description files name the registers of UART peripheral `UART_CR`, `UART_SR`,
+//! that generates `UART.uart_cr()`. This transformation renames them to `CR` and `SR`.
+//!
+//! The prefix is the peripheral name, the peripheral name without instance number
+//! (e.g. `UART` of `UART0`) or the group name of peripheral followed by `_`. Matching is case insensitive.
+//! A register is not renamed if its name without prefix is not a valid identifier or if it is the same
+//! name of another register or cluster of the same parent.
+use crate::{svd, Transform};
+use anyhow::Result;
+use log::{debug, warn};
+use std::collections::HashMap;
+
+/// Strip peripheral name prefix from names of registers and clusters
+pub(crate) struct StripPeripheralPrefix;
+
+impl Transform for StripPeripheralPrefix {
+    fn name(&self) -> &str {
+        "strip peripheral prefix"
+    }
+
+    fn apply(&self, device: &mut svd::Device) -> Result<()> {
+        for peripheral in device.peripherals.iter_mut() {
+            let prefixes = peripheral_prefixes(peripheral);
+            let name = peripheral.name.clone();
+            if let Some(registers) = peripheral.registers.as_mut() {
+                strip_registers(&name, &prefixes, registers);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Prefixes of peripheral in upper case, longest first
+fn peripheral_prefixes(peripheral: &svd::Peripheral) -> Vec<String> {
+    let name = peripheral.name.replace("[%s]", "").replace("%s", "");
+    let mut prefixes = vec![
+        name.trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_owned(),
+        name,
+    ];
+    prefixes.extend(peripheral.group_name.clone());
+    let mut prefixes: Vec<String> = prefixes
+        .into_iter()
+        .filter(|prefix| !prefix.is_empty())
+        .map(|prefix| format!("{}_", prefix.to_ascii_uppercase()))
+        .collect();
+    prefixes.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    prefixes.dedup();
+    prefixes
+}
+
+/// Name without the longest matching prefix, `None` if no prefix matches or the result is not a valid name
+fn strip_name(name: &str, prefixes: &[String]) -> Option<String> {
+    let upper_name = name.to_ascii_uppercase();
+    let stripped = prefixes
+        .iter()
+        .find(|prefix| upper_name.starts_with(prefix.as_str()))
+        .map(|prefix| &name[prefix.len()..])?;
+    stripped
+        .starts_with(|c: char| c.is_ascii_alphabetic())
+        .then(|| stripped.to_owned())
+}
+
+fn register_cluster_name(register: &svd::RegisterCluster) -> &str {
+    match register {
+        svd::RegisterCluster::Register(register) => &register.name,
+        svd::RegisterCluster::Cluster(cluster) => &cluster.name,
+    }
+}
+
+/// Strip prefix from children of peripheral or cluster, names that collide after stripping are kept.
+fn strip_registers(parent: &str, prefixes: &[String], registers: &mut [svd::RegisterCluster]) {
+    let new_names: Vec<Option<String>> = registers
+        .iter()
+        .map(|register| strip_name(register_cluster_name(register), prefixes))
+        .collect();
+    // Count all names after stripping to detect collisions
+    let mut count: HashMap<String, usize> = HashMap::new();
+    for (register, new_name) in registers.iter().zip(&new_names) {
+        let name = new_name
+            .as_deref()
+            .unwrap_or(register_cluster_name(register))
+            .to_ascii_uppercase();
+        *count.entry(name).or_default() += 1;
+    }
+    // Old and new names, used to update `derivedFrom` of siblings
+    let mut renamed = HashMap::new();
+    for (register, new_name) in registers.iter_mut().zip(new_names) {
+        let Some(new_name) = new_name else {
+            continue;
+        };
+        let name = match register {
+            svd::RegisterCluster::Register(register) => &mut register.name,
+            svd::RegisterCluster::Cluster(cluster) => &mut cluster.name,
+        };
+        if count[&new_name.to_ascii_uppercase()] > 1 {
+            warn!("{name} of {parent} is not renamed to {new_name} because of name collision");
+            continue;
+        }
+        debug!("Rename {name} of {parent} to {new_name}");
+        renamed.insert(name.clone(), new_name.clone());
+        *name = new_name;
+    }
+    for register in registers.iter_mut() {
+        let derived_from = match register {
+            svd::RegisterCluster::Register(register) => register.derived_from.as_mut(),
+            svd::RegisterCluster::Cluster(cluster) => {
+                let name = cluster.name.clone();
+                strip_registers(&name, prefixes, &mut cluster.children);
+                cluster.derived_from.as_mut()
+            }
+        };
+        if let Some(derived_from) = derived_from {
+            if let Some(new_name) = renamed.get(derived_from) {
+                derived_from.clone_from(new_name);
+            }
+        }
+    }
+}
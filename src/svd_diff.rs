@@ -0,0 +1,317 @@
+//! Register level comparison of two register descriptions, used by `diff` subcommand.
+//!
+//! Both devices are compared after resolving `derivedFrom`, arrays and inherited register properties,
+//! so a change of a base peripheral or of default register properties is reported for all items it affects
+//! and elements of arrays are compared one by one.
+//! Items are identified by their path, e.g. `TIMER.CTRL.EN`. Descriptions are not compared.
+use crate::svd;
+use anyhow::Result;
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Kind of change of an item
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Kind of compared item
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ItemKind {
+    Peripheral,
+    Register,
+    Field,
+    Interrupt,
+}
+
+/// Change of an item. Changed items have a change for each modified property.
+#[derive(Debug, Serialize)]
+pub(crate) struct Change {
+    pub kind: ChangeKind,
+    pub item: ItemKind,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+}
+
+/// Compared properties of an item by name
+type Properties = Vec<(&'static str, String)>;
+
+fn optional<T: std::fmt::Debug>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| format!("{value:?}"))
+}
+
+fn optional_hex(value: Option<u64>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| format!("{value:#x}"))
+}
+
+fn peripheral_properties(peripheral: &svd::Peripheral) -> Properties {
+    vec![("base address", format!("{:#x}", peripheral.base_address))]
+}
+
+fn field_properties(field: &svd::Field) -> Properties {
+    let enumerated_values = field
+        .enumerated_values
+        .iter()
+        .flat_map(|values| &values.values)
+        .map(|value| format!("{}={}", value.name, optional_hex(value.value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![
+        ("bit offset", field.bit_range.offset.to_string()),
+        ("bit width", field.bit_range.width.to_string()),
+        ("access", optional(field.access)),
+        ("enumerated values", enumerated_values),
+    ]
+}
+
+/// Register with its offset from peripheral base address and fields
+struct RegisterItem<'a> {
+    properties: Properties,
+    fields: LinkedHashMap<String, &'a svd::Field>,
+}
+
+/// Collect registers of peripheral or cluster by path relative to peripheral
+fn collect_registers<'a>(
+    registers: &'a [svd::RegisterCluster],
+    path_prefix: &str,
+    offset: u64,
+    result: &mut LinkedHashMap<String, RegisterItem<'a>>,
+) {
+    for register in registers {
+        match register {
+            svd::RegisterCluster::Register(register) => {
+                let properties = &register.properties;
+                result.insert(
+                    format!("{path_prefix}{}", register.name),
+                    RegisterItem {
+                        properties: vec![
+                            (
+                                "offset",
+                                format!("{:#x}", offset + register.address_offset as u64),
+                            ),
+                            ("size", optional(properties.size)),
+                            ("access", optional(properties.access)),
+                            ("reset value", optional_hex(properties.reset_value)),
+                            ("reset mask", optional_hex(properties.reset_mask)),
+                        ],
+                        fields: register
+                            .fields()
+                            .map(|field| (field.name.clone(), field))
+                            .collect(),
+                    },
+                );
+            }
+            svd::RegisterCluster::Cluster(cluster) => collect_registers(
+                &cluster.children,
+                &format!("{path_prefix}{}.", cluster.name),
+                offset + cluster.address_offset as u64,
+                result,
+            ),
+        }
+    }
+}
+
+/// Registers of peripheral by path
+fn peripheral_registers<'a>(
+    name: &str,
+    peripheral: &'a svd::Peripheral,
+) -> LinkedHashMap<String, RegisterItem<'a>> {
+    let mut result = LinkedHashMap::new();
+    collect_registers(
+        peripheral.registers.as_deref().unwrap_or(&[]),
+        &format!("{name}."),
+        0,
+        &mut result,
+    );
+    result
+}
+
+/// Report items of `old` that are not in `new` as removed, then items of `new` that are not in `old` as added.
+/// Properties of items in both are compared by `compare`.
+fn compare_items<'a, T>(
+    item: ItemKind,
+    old: &'a LinkedHashMap<String, T>,
+    new: &'a LinkedHashMap<String, T>,
+    changes: &mut Vec<Change>,
+    mut compare: impl FnMut(&'a str, &'a T, &'a T, &mut Vec<Change>),
+) {
+    let added_or_removed = |kind, path: &str| Change {
+        kind,
+        item,
+        path: path.to_owned(),
+        property: None,
+        old: None,
+        new: None,
+    };
+    for (path, old_item) in old {
+        match new.get(path) {
+            Some(new_item) => compare(path.as_str(), old_item, new_item, changes),
+            None => changes.push(added_or_removed(ChangeKind::Removed, path.as_str())),
+        }
+    }
+    for path in new.keys().filter(|path| !old.contains_key(*path)) {
+        changes.push(added_or_removed(ChangeKind::Added, path.as_str()));
+    }
+}
+
+fn compare_properties(
+    item: ItemKind,
+    path: &str,
+    old: &Properties,
+    new: &Properties,
+    changes: &mut Vec<Change>,
+) {
+    for ((property, old_value), (_, new_value)) in old.iter().zip(new) {
+        if old_value != new_value {
+            changes.push(Change {
+                kind: ChangeKind::Changed,
+                item,
+                path: path.to_owned(),
+                property: Some(*property),
+                old: Some(old_value.clone()),
+                new: Some(new_value.clone()),
+            });
+        }
+    }
+}
+
+/// Interrupts of all peripherals by name
+fn interrupts(device: &svd::Device) -> LinkedHashMap<String, Properties> {
+    device
+        .peripherals
+        .iter()
+        .flat_map(|peripheral| &peripheral.interrupt)
+        .map(|interrupt| {
+            (
+                interrupt.name.clone(),
+                vec![("value", interrupt.value.to_string())],
+            )
+        })
+        .collect()
+}
+
+/// Changes of peripherals, registers, fields and interrupts from `old` to `new` device
+pub(crate) fn diff_devices(old: &svd::Device, new: &svd::Device) -> Result<Vec<Change>> {
+    let normalize = |device: &svd::Device| -> Result<svd::Device> {
+        let mut device = svd_parser::expand(device)?;
+        svd_parser::expand_properties(&mut device);
+        Ok(device)
+    };
+    let (old, new) = (normalize(old)?, normalize(new)?);
+    let peripherals = |device: &svd::Device| -> LinkedHashMap<String, svd::Peripheral> {
+        device
+            .peripherals
+            .iter()
+            .map(|peripheral| (peripheral.name.clone(), peripheral.clone()))
+            .collect()
+    };
+    let (old_peripherals, new_peripherals) = (peripherals(&old), peripherals(&new));
+
+    let mut changes = Vec::new();
+    compare_items(
+        ItemKind::Peripheral,
+        &old_peripherals,
+        &new_peripherals,
+        &mut changes,
+        |name, old_peripheral, new_peripheral, changes| {
+            compare_properties(
+                ItemKind::Peripheral,
+                name,
+                &peripheral_properties(old_peripheral),
+                &peripheral_properties(new_peripheral),
+                changes,
+            );
+            let (old_registers, new_registers) = (
+                peripheral_registers(name, old_peripheral),
+                peripheral_registers(name, new_peripheral),
+            );
+            compare_items(
+                ItemKind::Register,
+                &old_registers,
+                &new_registers,
+                changes,
+                |path, old_register, new_register, changes| {
+                    compare_properties(
+                        ItemKind::Register,
+                        path,
+                        &old_register.properties,
+                        &new_register.properties,
+                        changes,
+                    );
+                    let field_path = |name: &String| format!("{path}.{name}");
+                    let fields = |register: &RegisterItem| -> LinkedHashMap<String, Properties> {
+                        register
+                            .fields
+                            .iter()
+                            .map(|(name, field)| (field_path(name), field_properties(field)))
+                            .collect()
+                    };
+                    let (old_fields, new_fields) = (fields(old_register), fields(new_register));
+                    compare_items(
+                        ItemKind::Field,
+                        &old_fields,
+                        &new_fields,
+                        changes,
+                        |path, old_field, new_field, changes| {
+                            compare_properties(ItemKind::Field, path, old_field, new_field, changes)
+                        },
+                    );
+                },
+            );
+        },
+    );
+    let (old_interrupts, new_interrupts) = (interrupts(&old), interrupts(&new));
+    compare_items(
+        ItemKind::Interrupt,
+        &old_interrupts,
+        &new_interrupts,
+        &mut changes,
+        |name, old_interrupt, new_interrupt, changes| {
+            compare_properties(
+                ItemKind::Interrupt,
+                name,
+                old_interrupt,
+                new_interrupt,
+                changes,
+            )
+        },
+    );
+    Ok(changes)
+}
+
+/// Human readable report with a line for each change. `+` added, `-` removed, `~` changed items.
+pub(crate) fn text_report(changes: &[Change]) -> Result<String> {
+    let mut report = String::new();
+    for change in changes {
+        let item = match change.item {
+            ItemKind::Peripheral => "peripheral",
+            ItemKind::Register => "register",
+            ItemKind::Field => "field",
+            ItemKind::Interrupt => "interrupt",
+        };
+        match change.kind {
+            ChangeKind::Added => writeln!(report, "+ {item} {}", change.path)?,
+            ChangeKind::Removed => writeln!(report, "- {item} {}", change.path)?,
+            ChangeKind::Changed => writeln!(
+                report,
+                "~ {item} {} {}: {} -> {}",
+                change.path,
+                change.property.unwrap_or_default(),
+                change.old.as_deref().unwrap_or_default(),
+                change.new.as_deref().unwrap_or_default()
+            )?,
+        }
+    }
+    writeln!(report, "{} changes", changes.len())?;
+    Ok(report)
+}
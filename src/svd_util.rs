@@ -50,7 +50,8 @@ impl ExpandedName for svd::Peripheral {
     }
 }
 
-/// Trait to ger headerStructName field
+/// Trait to get the name of the generated struct.
+/// It is defined by headerStructName field or by dimName field for arrays.
 pub(crate) trait HeaderStructName {
     fn header_struct_name(&self) -> Option<String>;
 }
@@ -63,7 +64,14 @@ impl HeaderStructName for svd::Peripheral {
 
 impl HeaderStructName for svd::Cluster {
     fn header_struct_name(&self) -> Option<String> {
-        self.header_struct_name.clone()
+        match self {
+            svd::MaybeArray::Single(info) => info.header_struct_name.clone(),
+            // headerStructName has priority over dimName
+            svd::MaybeArray::Array(info, dim_info) => info
+                .header_struct_name
+                .clone()
+                .or_else(|| dim_info.dim_name.clone()),
+        }
     }
 }
 
@@ -0,0 +1,52 @@
+//! User defined transformations of device description.
+use crate::svd;
+
+/// Transformation of the device description applied after parsing and patching and before code generation.
+///
+/// Transformations are passed to [`crate::main_with_transforms`] and applied in the given order.
+/// Closures with signature `Fn(&mut svd::Device) -> anyhow::Result<()>` implement this trait.
+///
+/// The device passed to [`Transform::apply`] already inherited size, access and reset value
+/// of registers from device, peripherals and clusters. After all transformations these properties
+/// are inherited again, so registers added or moved by a transformation may leave them unset.
+/// `derivedFrom` references are resolved during code generation and shall refer to existing elements.
+///
+/// # Examples
+///
+/// ```ignore
+/// use svd2pac::{svd, Transform};
+///
+/// struct RenameTimer;
+///
+/// impl Transform for RenameTimer {
+///     fn name(&self) -> &str {
+///         "rename timer"
+///     }
+///     fn apply(&self, device: &mut svd::Device) -> anyhow::Result<()> {
+///         for peripheral in device.peripherals.iter_mut() {
+///             if peripheral.name == "TIM0" {
+///                 peripheral.name = "TIMER0".to_owned();
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Transform {
+    /// Name of transformation used in log messages
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Apply transformation to device description
+    fn apply(&self, device: &mut svd::Device) -> anyhow::Result<()>;
+}
+
+impl<F> Transform for F
+where
+    F: Fn(&mut svd::Device) -> anyhow::Result<()>,
+{
+    fn apply(&self, device: &mut svd::Device) -> anyhow::Result<()> {
+        self(device)
+    }
+}
@@ -2,10 +2,22 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+/// Remove header line with generation time of generated files
+fn remove_generation_time(content: String) -> String {
+    content
+        .lines()
+        .filter(|line| !line.starts_with("// Generated from SVD"))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
 #[allow(dead_code)]
 pub fn assert_files_eq<T: AsRef<Path>, Q: AsRef<Path>>(ref_file: T, gen_file: Q) {
-    let ref_file = fs::read_to_string(ref_file).expect("Unable to read source file");
-    let gen_file = fs::read_to_string(gen_file).expect("Unable to read generated file");
+    let ref_file =
+        remove_generation_time(fs::read_to_string(ref_file).expect("Unable to read source file"));
+    let gen_file = remove_generation_time(
+        fs::read_to_string(gen_file).expect("Unable to read generated file"),
+    );
     let result = similar::TextDiff::from_lines(&ref_file, &gen_file);
     let diff = format!(
         "{}",
@@ -24,6 +36,19 @@ pub fn assert_files_eq<T: AsRef<Path>, Q: AsRef<Path>>(ref_file: T, gen_file: Q)
     );
 }
 
+/// Set default features of generated package
+#[allow(dead_code)]
+pub fn set_default_features(package_folder: &Path, features: &[&str]) {
+    let toml_path = package_folder.join("Cargo.toml");
+    let toml = fs::read_to_string(&toml_path).expect("Unable to read toml file");
+    let mut parsed_toml = toml
+        .parse::<toml_edit::Document>()
+        .expect("Unable to parse toml file");
+    parsed_toml["features"]["default"] =
+        toml_edit::value(features.iter().copied().collect::<toml_edit::Array>());
+    fs::write(&toml_path, parsed_toml.to_string()).expect("Unable to write toml file");
+}
+
 /// execute cargo build and check that build is successfull
 pub fn assert_cargo_build(package_folder: tempfile::TempDir) {
     // Run cargo to build
@@ -54,10 +79,16 @@ pub fn assert_cargo_build(package_folder: tempfile::TempDir) {
 
 #[allow(dead_code)]
 pub fn assert_cargo_test(package_folder: tempfile::TempDir) {
+    assert_cargo_test_in(package_folder, ".");
+}
+
+/// execute cargo test in a sub folder of package folder and check that tests pass
+#[allow(dead_code)]
+pub fn assert_cargo_test_in(package_folder: tempfile::TempDir, sub_folder: &str) {
     // Run cargo to build
     let mut command = Command::new("cargo");
     command.arg("test");
-    command.current_dir(package_folder.path());
+    command.current_dir(package_folder.path().join(sub_folder));
 
     let exec_result = command.output();
 
@@ -72,3 +103,42 @@ pub fn assert_cargo_test(package_folder: tempfile::TempDir) {
         panic!("Failed running tests of test project");
     }
 }
+
+/// Register emulation used by tests run in packages generated with `--tracing`.
+/// Each register is a memory location of the current test thread.
+#[allow(dead_code)]
+const REGISTER_MEMORY: &str = r#"
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static MEMORY: RefCell<HashMap<usize, u64>> = RefCell::new(HashMap::new());
+}
+
+fn read_fn(addr: usize, _len: usize) -> u64 {
+    MEMORY.with(|memory| memory.borrow().get(&addr).copied().unwrap_or(0))
+}
+
+fn write_fn(addr: usize, _len: usize, val: u64) {
+    MEMORY.with(|memory| memory.borrow_mut().insert(addr, val));
+}
+
+/// Redirect register accesses of current thread to emulated registers
+fn init_register_memory() {
+    test_pac::tracing::set_read_fn(read_fn).unwrap();
+    test_pac::tracing::set_write_fn(write_fn).unwrap();
+}
+"#;
+
+/// Add test to generated package and check that tests of package pass.
+/// Package shall be generated with `--tracing`, test code can call `init_register_memory()` to emulate registers.
+#[allow(dead_code)]
+pub fn assert_generated_test(package_folder: tempfile::TempDir, test_code: &str) {
+    fs::create_dir_all(package_folder.path().join("tests")).expect("Unable to create tests folder");
+    fs::write(
+        package_folder.path().join("tests/generated_api.rs"),
+        format!("{REGISTER_MEMORY}{test_code}"),
+    )
+    .expect("Unable to write test of generated package");
+    assert_cargo_test(package_folder);
+}
@@ -76,6 +76,16 @@ fn main() -> ! {
                 .set(3)
         });
 
+        // Service request helpers
+        service_request::TIMER_0.route(10, service_request::type_of_service::CPU_0);
+        service_request::TIMER_0.enable();
+        for node in service_request::peripheral::FOO {
+            node.disable();
+        }
+        if service_request::by_index(0).is_some_and(|node| node.is_requested()) {
+            service_request::FOO.clear_request();
+        }
+
         //Tests related to csfr registers
         let register = CSFR_CPU.biv();
         let register_value = register.read();
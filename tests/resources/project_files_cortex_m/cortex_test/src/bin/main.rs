@@ -14,6 +14,10 @@ mod app {
     #[init]
     fn init(ctx: init::Context) -> (Shared, Local) {
         let device: test_pac::Peripherals = ctx.device;
+        // Priorities are checked against implemented priority bits
+        let priority = test_pac::Priority::new(2).unwrap();
+        assert!(test_pac::Priority::new(1 << test_pac::NVIC_PRIO_BITS).is_none());
+        let _raw_priority: u8 = priority.into();
         unsafe {
             // it is possible to use standard way of accessing register through Peripheral type
             device.TIMER.bitfield_reg().init(|f| {
@@ -39,6 +43,10 @@ mod app {
 
     #[task(binds = INT_FOO, local = [times: u32 = 0])]
     fn set_some_regs(_cx: set_some_regs::Context) {
+        // Interrupt number of active vector can be converted to interrupt
+        if let cortex_m::peripheral::scb::VectActive::Interrupt { irqn } = SCB::vect_active() {
+            let _interrupt = Interrupt::try_from(irqn as u16);
+        }
         // Peripheral type is not required
         unsafe {
             TIMER.bitfield_reg().modify(|f| {
@@ -47,6 +55,17 @@ mod app {
                     .bitfieldw()
                     .set(3)
             });
+            // Set and clear flag without read/modify/write using bit-band alias
+            TIMER.bitfield_reg().set_atomic(|f| f.boolrw());
+            TIMER.bitfield_reg().clear_atomic(|f| f.boolrw());
+            // Read/modify/write inside critical section
+            TIMER
+                .bitfield_reg()
+                .modify_critical(|f| f.bitfieldrw().set(1));
+            // Only bitfieldrw is written with interrupts masked
+            TIMER
+                .bitfield_reg()
+                .modify_atomic(|f| f.bitfieldrw().set(5));
         }
     }
 }
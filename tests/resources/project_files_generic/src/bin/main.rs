@@ -27,6 +27,14 @@ fn main() -> ! {
                 .set(3)
         });
 
+        // Modify with early abort
+        let _: Result<(), ()> = TIMER.bitfield_reg().try_modify(|f| {
+            if f.boolr().get() {
+                return Err(());
+            }
+            Ok(f.boolrw().set(true))
+        });
+
         // init
         TIMER.bitfield_reg().init(|f| {
             f.bitfieldenumerated()
@@ -43,6 +51,14 @@ fn main() -> ! {
                 .set(timer::bitfield_reg::FieldArray::FALLING);
         }
         TIMER.bitfield_reg().write(a);
+        if let Some(field) = a.fieldarray_get(1) {
+            a = field.set(timer::bitfield_reg::FieldArray::RISING);
+        }
+        assert!(a.fieldarray_get(8).is_none());
+
+        // Array of register bitfields with gaps between elements
+        let gapped = TIMER.register64bit().read().set_raw(0).gapped(1).set(3);
+        assert_eq!(gapped.get_raw(), 3 << 12);
 
         // Array of registers
         let reg_array = TIMER.arrayreg();
@@ -68,20 +84,40 @@ fn main() -> ! {
                 .set(3)
         });
 
+        // Single bit helpers
+        TIMER.bitfield_reg().modify(|f| f.boolrw().set_bit().boolw().clear_bit());
+        TIMER.bitfield_reg().modify(|f| f.boolrw().toggle());
+
         // Use register marked with alternateGroup
         TIMER.bitfield_reg_alt_group().modify(|f| f.set(32));
 
         // Set and get raw values
         TIMER.bitfield_reg().modify(|f| f.set_raw(32));
         let _: u32 = TIMER.bitfield_reg().read().get_raw();
+        TIMER.bitfield_reg().write_raw(0x1234);
+        let _: u32 = TIMER.bitfield_reg().read_raw();
+
+        // Manipulate raw bits selected by mask
+        TIMER
+            .bitfield_reg()
+            .modify(|f| f.set_bits(0x1).clear_bits(0x2).update_masked(0xf00, 0x300));
+
+        // Manipulate only bits that belong to a bitfield
+        TIMER
+            .bitfield_reg()
+            .modify(|f| f.update_masked(timer::BitfieldReg_SPEC::FIELDS_MASK, 0x1234));
 
         // Get mask and offset for a register bitfield
         let register_bitfield = TIMER.bitfield_reg().read().bitfieldr();
         let _offset = register_bitfield.offset();
         let _mask = register_bitfield.mask();
+        const _: () = assert!(timer::BitfieldReg::BITFIELDR_OFFSET == 3);
+        const _: () = assert!(timer::BitfieldReg::BITFIELDR_WIDTH == 3);
+        const _: () = assert!(timer::BitfieldReg::BITFIELDR_MASK == 0x7);
 
         // Get mask and offset for a register bitfield fro boolean
         let register_bitfield = TIMER.bitfield_reg().read().boolr();
+        assert_eq!(register_bitfield.is_set(), !register_bitfield.is_clear());
         let _offset = register_bitfield.offset();
         let _mask = register_bitfield.mask();
 
@@ -91,6 +127,84 @@ fn main() -> ! {
         FOO.r#in()
             .write(value._self().set(foo::r#in::_Self::_1_VALUE));
 
+        // Bitfield without access inherits it from register
+        let _busy: bool = TIMER.sr().read().busy().get();
+
+        // Enumerated bitfield value only if documented in register description
+        let _run: Option<timer::sr::Run> = TIMER.sr().read().run().variant();
+
+        // Check enumerated bitfield value with predicate
+        let _running: bool = TIMER.sr().read().run().is_running();
+
+        // Wait for bitfield value with limited number of reads
+        let mut budget = 10;
+        let _ = TIMER.sr().wait_until_timeout(
+            |r| r.run().is_running(),
+            || {
+                budget -= 1;
+                budget > 0
+            },
+        );
+
+        // Print register value with bitfields
+        let _ = format!("{:?}", TIMER.sr().read());
+        let _ = format!("{:?}", TIMER.nobitfield_reg().read());
+
+        // Convert enumerated value from and to name in register description
+        let run: timer::sr::Run = "Running".parse().unwrap();
+        let _ = format!("{}", run);
+
+        // Compare register values
+        let _equal: bool = TIMER.bitfield_reg().read() == timer::BitfieldReg::default();
+
+        // Address and offset of registers
+        assert_eq!(
+            TIMER.bitfield_reg().addr(),
+            timer::BitfieldReg_SPEC::ADDRESS
+        );
+        let _offset: usize = timer::BitfieldReg_SPEC::OFFSET;
+
+        // Restore reset value
+        const _RESET: timer::BitfieldReg = timer::BitfieldReg::RESET;
+        TIMER.bitfield_reg().reset();
+
+        // Peripheral metadata
+        assert_eq!(Timer::NAME, "TIMER");
+        assert_eq!(TIMER.base_addr(), Timer::BASE_ADDR);
+        assert_eq!(UART[1].index(), Some(1));
+        let relocated = Timer::from_ptr(Timer::BASE_ADDR as *mut u8);
+        assert!(relocated == TIMER);
+        let dump = TIMER.dump();
+        assert!(dump
+            .iter()
+            .any(|(name, address, _)| *name == "BITFIELD_REG"
+                && *address == TIMER.bitfield_reg().addr()));
+
+        // Decode raw values of registers
+        let decoded = TIMER.bitfield_reg().decode(0x4);
+        assert_eq!(decoded.name, "BITFIELD_REG");
+        assert!(decoded
+            .field_values()
+            .any(|(name, value)| name == "boolrw" && value == 1));
+        assert!(decode(TIMER.bitfield_reg().addr(), 0x4) == Some(decoded));
+        assert!(decode(UART[1].reg32bitraw().addr(), 0x4).is_some());
+        assert!(decode(0, 0).is_none());
+        let _ = TIMER.bitfield_reg().read().decode();
+
+        // Plain repr(C) view of peripheral
+        let raw: *mut timer::RegisterBlock = TIMER.as_raw_ptr();
+        let _ = core::ptr::addr_of!((*raw).bitfield_reg).read_volatile();
+        let _zero = timer::RegisterBlock::ZERO;
+
+        // Derived peripherals share the struct of their parent
+        let derived: P33 = DERIVEDPERIPHERAL;
+        let _ = derived.i2c2().reg1().read();
+
+        // Struct of cluster array is named as dimName
+        let _cluster: timer::ClusterDimElement = TIMER.clusterdim()[0];
+        assert!(TIMER.clusterdim_get(0) == Some(TIMER.clusterdim()[0]));
+        assert!(TIMER.arrayreg_get(TIMER.arrayreg().len()).is_none());
+
         // Test 64Bit register
         TIMER.register64bit().modify(|r| r.boolean().set(crate::timer::register64bit::Boolean::FALSE));
     }
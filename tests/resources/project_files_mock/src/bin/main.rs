@@ -0,0 +1,93 @@
+use test_pac::*;
+fn main() {
+    unsafe {
+        TIMER.bitfield_reg().modify(|f| f.boolrw().set(true));
+        let _ = TIMER.bitfield_reg().read().boolrw().get();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_pac::*;
+
+    #[test]
+    fn reset_value() {
+        mock::reset_mocks();
+        assert_eq!(
+            unsafe { TIMER.register64bit().read_raw() },
+            0xFFFFFFFFFFFFFFFF
+        );
+    }
+
+    #[test]
+    fn write_read() {
+        mock::reset_mocks();
+        let reset = mock::get(TIMER.bitfield_reg().addr());
+        unsafe {
+            TIMER.bitfield_reg().write_raw(0x1234);
+            assert_eq!(TIMER.bitfield_reg().read_raw(), 0x1234);
+        }
+        assert_eq!(mock::get(TIMER.bitfield_reg().addr()), 0x1234);
+        mock::reset_mocks();
+        assert_eq!(mock::get(TIMER.bitfield_reg().addr()), reset);
+    }
+
+    #[test]
+    fn read_only_register() {
+        mock::reset_mocks();
+        mock::set(TIMER.sr().addr(), 0x1);
+        assert_eq!(unsafe { TIMER.sr().read_raw() }, 0x1);
+    }
+
+    #[test]
+    fn read_hook() {
+        mock::reset_mocks();
+        mock::set(TIMER.sr().addr(), 0x2);
+        // BUSY is cleared after 3 reads
+        let mut reads = 0;
+        mock::on_read(TIMER.sr(), move |value| {
+            reads += 1;
+            if reads >= 3 {
+                value.set_raw(value.get_raw() & !0x2)
+            } else {
+                value
+            }
+        });
+        assert!(unsafe { TIMER.sr().read().busy().get() });
+        assert!(unsafe { TIMER.sr().read().busy().get() });
+        assert!(!unsafe { TIMER.sr().read().busy().get() });
+        assert_eq!(mock::get(TIMER.sr().addr()), 0x0);
+        mock::remove_hooks(TIMER.sr());
+        mock::set(TIMER.sr().addr(), 0x2);
+        assert!(unsafe { TIMER.sr().read().busy().get() });
+    }
+
+    #[test]
+    fn write_hook() {
+        mock::reset_mocks();
+        mock::set(TIMER.bitfield_reg().addr(), 0xF0);
+        // bits are cleared writing 1
+        mock::on_write(TIMER.bitfield_reg(), |current, written| {
+            current.set_raw(current.get_raw() & !written.get_raw())
+        });
+        unsafe { TIMER.bitfield_reg().write_raw(0x30) };
+        assert_eq!(mock::get(TIMER.bitfield_reg().addr()), 0xC0);
+        // hooks are removed by reset
+        mock::reset_mocks();
+        unsafe { TIMER.bitfield_reg().write_raw(0x30) };
+        assert_eq!(mock::get(TIMER.bitfield_reg().addr()), 0x30);
+    }
+
+    #[test]
+    fn own_handler() {
+        mock::reset_mocks();
+        assert!(tracing::set_read_fn(|_, _| 0x5678).is_ok());
+        assert!(tracing::set_write_fn(|_, _, _| {}).is_ok());
+        unsafe {
+            TIMER.bitfield_reg().write_raw(0x1234);
+            assert_eq!(TIMER.bitfield_reg().read_raw(), 0x5678);
+        }
+        // Register file is not used by threads with own handlers
+        assert_ne!(mock::get(TIMER.bitfield_reg().addr()), 0x1234);
+    }
+}
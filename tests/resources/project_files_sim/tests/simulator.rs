@@ -0,0 +1,91 @@
+use test_pac::{TIMER, UART};
+use test_pac_sim::*;
+
+/// Timer whose busy flag of status register is cleared after 3 reads
+struct BusyTimer {
+    timer: Timer,
+    reads: u32,
+}
+
+impl Peripheral for BusyTimer {
+    fn name(&self) -> &'static str {
+        self.timer.name()
+    }
+
+    fn base_address(&self) -> usize {
+        self.timer.base_address()
+    }
+
+    fn get(&self, offset: usize) -> Option<u64> {
+        self.timer.get(offset)
+    }
+
+    fn set(&mut self, offset: usize, value: u64) -> bool {
+        self.timer.set(offset, value)
+    }
+
+    fn reset(&mut self) {
+        self.timer.reset();
+        self.reads = 0;
+    }
+
+    fn on_read(&mut self, offset: usize, len: usize) -> u64 {
+        if offset == TIMER.sr().addr() - self.base_address() {
+            self.reads += 1;
+            if self.reads >= 3 {
+                self.timer.sr &= !0x2;
+            }
+        }
+        self.timer.on_read(offset, len)
+    }
+}
+
+#[test]
+fn reset_value() {
+    install().unwrap();
+    assert_eq!(
+        unsafe { TIMER.register64bit().read_raw() },
+        0xFFFFFFFFFFFFFFFF
+    );
+}
+
+#[test]
+fn write_read() {
+    install().unwrap();
+    let addr = TIMER.bitfield_reg().addr();
+    let reset = with_simulator(|simulator| simulator.get(addr)).unwrap();
+    unsafe {
+        TIMER.bitfield_reg().write_raw(0x1234);
+        assert_eq!(TIMER.bitfield_reg().read_raw(), 0x1234);
+    }
+    assert_eq!(
+        with_simulator(|simulator| simulator.get(addr)),
+        Some(0x1234)
+    );
+    with_simulator(|simulator| simulator.reset());
+    assert_eq!(with_simulator(|simulator| simulator.get(addr)), Some(reset));
+}
+
+#[test]
+fn peripheral_array() {
+    install().unwrap();
+    unsafe { UART[1].reg32bitraw().write_raw(0xC0FFEE) };
+    let offset = UART[1].reg32bitraw().addr() - Uart::BASE_ADDRESSES[1];
+    with_simulator(|simulator| {
+        let uart = simulator.peripheral(Uart::BASE_ADDRESSES[1]).unwrap();
+        assert_eq!(uart.get(offset), Some(0xC0FFEE));
+        let uart = simulator.peripheral(Uart::BASE_ADDRESSES[0]).unwrap();
+        assert_ne!(uart.get(offset), Some(0xC0FFEE));
+    });
+}
+
+#[test]
+fn custom_peripheral() {
+    install().unwrap();
+    let mut timer = Timer::new(0);
+    timer.sr = 0x2;
+    with_simulator(|simulator| simulator.add(Box::new(BusyTimer { timer, reads: 0 })));
+    assert!(unsafe { TIMER.sr().read().busy().get() });
+    assert!(unsafe { TIMER.sr().read().busy().get() });
+    assert!(!unsafe { TIMER.sr().read().busy().get() });
+}
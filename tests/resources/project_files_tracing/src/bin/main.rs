@@ -84,7 +84,10 @@ fn main() -> ! {
 
 #[cfg(test)]
 mod test {
-    use test::{reg_name::reg_name_from_addr, *};
+    use test::{
+        reg_name::{addr_from_reg_name, reg_name_from_addr},
+        *,
+    };
     fn assert_regname(addr: usize, name_expected: &str) {
         match reg_name_from_addr(addr as u64) {
             Some(name) => {
@@ -99,6 +102,229 @@ mod test {
             }
         }
     }
+    thread_local! {
+        static WRITES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+    fn count_write(_addr: usize, _len: usize, _val: u64) {
+        WRITES.with(|writes| writes.set(writes.get() + 1));
+    }
+    #[test]
+    fn trace_peripheral_test() {
+        tracing::set_write_fn(count_write).unwrap();
+        tracing::trace_peripheral("UART").unwrap();
+        assert!(tracing::trace_peripheral("NOT_A_PERIPHERAL").is_err());
+        unsafe {
+            TIMER.nobitfield_reg().write_raw(1);
+            UART[0].reg32bitraw().write_raw(1);
+        }
+        assert_eq!(WRITES.with(|writes| writes.get()), 1);
+        tracing::trace_all();
+        unsafe { TIMER.nobitfield_reg().write_raw(1) };
+        assert_eq!(WRITES.with(|writes| writes.get()), 2);
+    }
+    static HOOKED_WRITES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    fn count_hooked_write(addr: usize, len: usize, val: u64) {
+        // Other tests are executed in parallel, count only writes of this test
+        if addr == UART[2].reg32bitraw().addr() && len == 4 && val == 0x1234_5678 {
+            HOOKED_WRITES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+    #[test]
+    fn write_hook_test() {
+        tracing::set_write_fn(ignore_write).unwrap();
+        hook::set_write_hook(count_hooked_write);
+        unsafe { UART[2].reg32bitraw().write_raw(0x1234_5678) };
+        assert_eq!(HOOKED_WRITES.load(std::sync::atomic::Ordering::Relaxed), 1);
+        hook::clear_write_hook();
+        unsafe { UART[2].reg32bitraw().write_raw(0x1234_5678) };
+        assert_eq!(HOOKED_WRITES.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+    fn read_constant(_addr: usize, _len: usize) -> u64 {
+        0x55
+    }
+    fn ignore_write(_addr: usize, _len: usize, _val: u64) {}
+    #[test]
+    fn record_replay_test() {
+        tracing::set_read_fn(read_constant).unwrap();
+        tracing::set_write_fn(ignore_write).unwrap();
+        tracing::record::start_recording();
+        unsafe {
+            TIMER.nobitfield_reg().write_raw(1);
+            let _ = TIMER.nobitfield_reg().read_raw();
+        }
+        let trace = tracing::record::stop_recording();
+        assert_eq!(trace.len(), 2);
+        assert_eq!((trace[0].timestamp, trace[0].core_id), (None, None));
+        let bytes = tracing::record::to_bytes(&trace);
+        // Replay handlers are installed in a new thread with no handlers
+        let replayed = std::thread::spawn(move || {
+            tracing::record::replay(tracing::record::from_bytes(&bytes).unwrap()).unwrap();
+            let value = unsafe {
+                TIMER.nobitfield_reg().write_raw(1);
+                TIMER.nobitfield_reg().read_raw()
+            };
+            (value, tracing::record::replay_remaining())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(replayed, (0x55, 0));
+    }
+    static GLOBAL_WRITES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    fn count_global_write(addr: usize, _len: usize, val: u64) {
+        // Other tests are executed in parallel, count only writes of this test
+        if addr == UART[1].reg32bitraw().addr() && val == 0xC0FFEE {
+            GLOBAL_WRITES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+    #[test]
+    fn global_handler_test() {
+        tracing::set_global_write_fn(count_global_write).unwrap();
+        assert!(tracing::set_global_write_fn(count_global_write).is_err());
+        // Thread without own handlers uses global one
+        std::thread::spawn(|| unsafe { UART[1].reg32bitraw().write_raw(0xC0FFEE) })
+            .join()
+            .unwrap();
+        assert_eq!(GLOBAL_WRITES.load(std::sync::atomic::Ordering::Relaxed), 1);
+        // Handler of thread has priority over global one
+        tracing::set_write_fn(ignore_write).unwrap();
+        unsafe { UART[1].reg32bitraw().write_raw(0xC0FFEE) };
+        assert_eq!(GLOBAL_WRITES.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+    #[test]
+    fn timestamp_core_id_test() {
+        tracing::set_write_fn(ignore_write).unwrap();
+        tracing::record::set_timestamp_fn(|| 1234);
+        tracing::record::set_core_id_fn(|| 1);
+        tracing::record::start_recording();
+        unsafe { TIMER.nobitfield_reg().write_raw(1) };
+        let trace = tracing::record::stop_recording();
+        assert_eq!(
+            (trace[0].timestamp, trace[0].core_id),
+            (Some(1234), Some(1))
+        );
+        let bytes = tracing::record::to_bytes(&trace);
+        let decoded = tracing::record::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            (decoded[0].timestamp, decoded[0].core_id),
+            (Some(1234), Some(1))
+        );
+    }
+    #[test]
+    fn caller_location_test() {
+        tracing::set_read_fn(read_constant).unwrap();
+        tracing::set_write_fn(ignore_write).unwrap();
+        tracing::record::start_recording();
+        let line = line!() + 1;
+        unsafe { TIMER.bitfield_reg().modify(|r| r.boolrw().set(true)) };
+        let trace = tracing::record::stop_recording();
+        let location = trace[0].location.unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
+        assert_eq!(tracing::caller().unwrap().line(), line);
+    }
+    #[test]
+    fn stats_test() {
+        tracing::set_read_fn(read_constant).unwrap();
+        tracing::set_write_fn(ignore_write).unwrap();
+        tracing::stats::enable();
+        unsafe {
+            TIMER.bitfield_reg().modify(|r| r.boolrw().set(true));
+            TIMER.nobitfield_reg().write_raw(1);
+            TIMER.nobitfield_reg().write_raw(2);
+        }
+        let counters = tracing::stats::counters(TIMER.bitfield_reg().addr());
+        assert_eq!((counters.reads, counters.writes), (1, 1));
+        assert_eq!(
+            tracing::stats::counters(TIMER.nobitfield_reg().addr()).writes,
+            2
+        );
+        assert_eq!(tracing::stats::counters(TIMER.sr().addr()).total(), 0);
+        let hot_registers = tracing::stats::hot_registers();
+        assert_eq!(hot_registers.len(), 2);
+        tracing::stats::reset();
+        assert!(tracing::stats::hot_registers().is_empty());
+    }
+    #[test]
+    fn coverage_test() {
+        let access = |kind, addr, value| tracing::record::Access {
+            kind,
+            addr,
+            len: 4,
+            value,
+            timestamp: None,
+            core_id: None,
+            location: None,
+        };
+        let trace = [
+            access(
+                tracing::record::AccessKind::Write,
+                TIMER.bitfield_reg().addr(),
+                0x0,
+            ),
+            access(
+                tracing::record::AccessKind::Write,
+                TIMER.bitfield_reg().addr(),
+                0x1,
+            ),
+            access(
+                tracing::record::AccessKind::Read,
+                UART[1].reg32bitraw().addr(),
+                0x0,
+            ),
+        ];
+        let report = tracing::coverage::report(&trace);
+        let timer = report.iter().find(|p| p.name == "TIMER").unwrap();
+        let bitfield_reg = timer
+            .registers
+            .iter()
+            .find(|r| r.addr == TIMER.bitfield_reg().addr())
+            .unwrap();
+        assert_eq!((bitfield_reg.reads, bitfield_reg.writes), (0, 2));
+        // Only BoolR at bit 0 has been toggled
+        assert_eq!(bitfield_reg.toggled_fields(), 1);
+        assert!(bitfield_reg.fields[0].toggled);
+        assert_eq!(timer.accessed_registers(), 1);
+        let uart = report.iter().find(|p| p.name == "UART[1]").unwrap();
+        assert_eq!(uart.accessed_registers(), 1);
+        assert_eq!(uart.toggled_fields(), 0);
+        let uart = report.iter().find(|p| p.name == "UART[0]").unwrap();
+        assert_eq!(uart.accessed_registers(), 0);
+        assert!(timer.to_string().starts_with("TIMER: registers 1/"));
+    }
+    fn read_and_interleave(addr: usize, _len: usize) -> u64 {
+        if addr == UART[2].reg16bitraw().addr() {
+            // Another thread writes register between read and write of modify
+            std::thread::spawn(|| {
+                tracing::set_write_fn(ignore_write).unwrap();
+                unsafe { UART[2].reg16bitraw().write_raw(1) };
+            })
+            .join()
+            .unwrap();
+        }
+        0
+    }
+    #[test]
+    fn race_test() {
+        tracing::set_read_fn(read_and_interleave).unwrap();
+        tracing::set_write_fn(ignore_write).unwrap();
+        tracing::race::enable();
+        let line = line!() + 1;
+        unsafe { UART[2].reg16bitraw().modify(|r| r) };
+        unsafe { UART[1].reg16bitraw().modify(|r| r) };
+        let races: Vec<_> = tracing::race::take_races()
+            .into_iter()
+            .filter(|race| {
+                race.addr == UART[2].reg16bitraw().addr()
+                    || race.addr == UART[1].reg16bitraw().addr()
+            })
+            .collect();
+        assert_eq!(races.len(), 1);
+        assert_eq!(races[0].addr, UART[2].reg16bitraw().addr());
+        assert_eq!(races[0].interleaved, 1);
+        assert_eq!(
+            (races[0].location.file(), races[0].location.line()),
+            (file!(), line)
+        );
+    }
     #[test]
     fn reg_name_test() {
         TIMER.arrayreg()[0].addr();
@@ -118,4 +344,31 @@ mod test {
         assert_regname(UART[0].reg8bitraw().addr(), "UART[0].reg8bitraw()");
         assert_regname(UART[0].regbitfieldraw().addr(), "UART[0].regbitfieldraw()");
     }
+    #[test]
+    fn addr_from_reg_name_test() {
+        assert_eq!(
+            addr_from_reg_name("TIMER.bitfield_reg()"),
+            Some(TIMER.bitfield_reg().addr())
+        );
+        assert_eq!(
+            addr_from_reg_name("TIMER.arrayreg()[1]"),
+            Some(TIMER.arrayreg()[1].addr())
+        );
+        assert_eq!(
+            addr_from_reg_name("UART[1].reg1_()[0]"),
+            Some(UART[1].reg1_()[0].addr())
+        );
+        assert_eq!(addr_from_reg_name(" TIMER.sr() "), Some(TIMER.sr().addr()));
+        assert_eq!(addr_from_reg_name("TIMER.unknown()"), None);
+        // round trip
+        let addr = TIMER.int().addr();
+        let names = reg_name_from_addr(addr as u64).unwrap();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            assert_eq!(addr_from_reg_name(name), Some(addr));
+        }
+    }
 }
@@ -0,0 +1,22 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use test_pac::{tracing, *};
+
+static WRITTEN: AtomicU64 = AtomicU64::new(0);
+
+fn read_fn(_addr: usize, _len: usize) -> u64 {
+    0xFFFF_F0FF
+}
+fn write_fn(_addr: usize, _len: usize, val: u64) {
+    WRITTEN.store(val, Ordering::Relaxed);
+}
+
+#[test]
+fn modify_atomic_with_tracing() {
+    let _ = tracing::set_read_fn(read_fn);
+    let _ = tracing::set_write_fn(write_fn);
+    unsafe {
+        TIMER.bitfield_reg().modify_atomic(|r| r.bitfieldrw().set(5));
+    }
+    // Only the bits of BitfieldRW are replaced
+    assert_eq!(WRITTEN.load(Ordering::Relaxed), 0xFFFF_F5FF);
+}
@@ -0,0 +1,59 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test code generation from ATDF register description.
+#[test]
+fn compile_generated_atdf() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_atdf.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--license-file",
+        "./tests/resources/LICENSE.txt",
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_path = generated_code_folder.path().join("src/tc0.rs");
+    assert!(
+        lib_path.exists(),
+        "Not found module of ATDF module instance"
+    );
+    let tc0_code = fs::read_to_string(&lib_path).expect("Unable to read tc0.rs");
+    assert!(
+        tc0_code.contains("pub const fn icr0(&self) -> [crate::common::Reg<"),
+        "Not found array of ATDF register with count"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Bitfield with empty mask is an error
+#[test]
+#[should_panic]
+fn test_atdf_empty_bitfield_mask() {
+    let atdf = fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/simple_atdf.xml"
+    ))
+    .expect("Unable to read ATDF file")
+    .replace(r#"mask="0x08""#, r#"mask="0x00""#);
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let atdf_path = generated_code_folder.path().join("empty_mask.atdf");
+    fs::write(&atdf_path, atdf).expect("Unable to write ATDF file");
+    let args = [
+        "",
+        atdf_path.to_str().unwrap(),
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+}
@@ -0,0 +1,56 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test avr target code generation.
+#[test]
+fn compile_generated_avr() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_atdf.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=avr",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("fn vector_symbol"),
+        "Not found vector symbols of interrupts"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Registers without size in SVD are 8 bit wide for avr target.
+#[test]
+fn test_avr_default_register_size() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/no_register_size.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=avr",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let port_code = fs::read_to_string(generated_code_folder.path().join("src/port.rs"))
+        .expect("Unable to read port.rs");
+    assert!(
+        port_code.contains("type DataType = u8;"),
+        "Register without size is not 8 bit wide"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
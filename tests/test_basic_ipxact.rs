@@ -0,0 +1,40 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test code generation from IP-XACT register description.
+#[test]
+fn compile_generated_ipxact() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_ipxact.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--license-file",
+        "./tests/resources/LICENSE.txt",
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_path = generated_code_folder.path().join("src/timer.rs");
+    assert!(
+        lib_path.exists(),
+        "Not found module of IP-XACT address block"
+    );
+    let timer_code = fs::read_to_string(&lib_path).expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub const fn cmp(&self) -> [crate::common::Reg<"),
+        "Not found array of IP-XACT register with dim"
+    );
+    assert!(
+        timer_code.contains("0xffff0000"),
+        "Not found reset value of register computed from its fields"
+    );
+    assert_cargo_build(generated_code_folder);
+}
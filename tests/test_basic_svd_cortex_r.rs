@@ -0,0 +1,26 @@
+mod common;
+use common::*;
+use std::env;
+use svd2pac::main_parse_arguments;
+
+/// Test cortex-r target code generation.
+#[test]
+fn compile_generated_cortex_r() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=cortex-r",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all", "rt"]);
+
+    let device_x_path = generated_code_folder.path().join("device.x");
+    assert!(device_x_path.exists(), "Not found device.x");
+    assert_cargo_build(generated_code_folder);
+}
@@ -2,6 +2,7 @@ mod common;
 use common::*;
 use fs_extra::dir::CopyOptions;
 use std::fs;
+use std::io::Write;
 use std::{env, path::Path};
 use svd2pac::main_parse_arguments;
 use toml_edit::{array, value, Array, Document, Table};
@@ -24,6 +25,7 @@ fn compile_generated_generic() {
         .expect("Unable to parse toml file");
     let mut default_table = Array::new();
     default_table.push("all");
+    default_table.push("debug-fields");
     parsed_toml["features"]["default"] = value(default_table);
     parsed_toml["bin"] = array();
     let bin_array = parsed_toml["bin"].as_array_of_tables_mut().unwrap();
@@ -53,6 +55,148 @@ fn compile_generated_generic() {
     assert_cargo_build(generated_code_folder);
 }
 
+/// `%s` placeholder of array descriptions is replaced by the index of each element
+/// of unrolled arrays and HTML formatting is removed from doc comments
+#[test]
+fn test_array_descriptions() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/descriptions.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = ["", xml_path, generated_code_folder.path().to_str().unwrap()];
+    main_parse_arguments(args);
+    let cpu_code = fs::read_to_string(generated_code_folder.path().join("src/cpu0.rs"))
+        .expect("Unable to read cpu0.rs");
+    assert!(!cpu_code.contains("%s"), "Found placeholder of array");
+    assert!(
+        cpu_code.contains(r#"#[doc = "CPUx Data Protection Range `index`, Lower Bound Register"]"#),
+        "Not found description of register array"
+    );
+    assert!(
+        cpu_code.contains(r#"#[doc = "Lower Boundary Address\nof range"]"#),
+        "Line break and whitespaces not normalized"
+    );
+    assert!(!cpu_code.contains("<b>"), "Found HTML tag");
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Bitfield arrays can have gaps between elements. Arrays whose last element exceeds the register are skipped.
+#[test]
+fn test_gapped_bitfield_arrays() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/bitfield_arrays.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = ["", xml_path, generated_code_folder.path().to_str().unwrap()];
+    main_parse_arguments(args);
+    let port_code = fs::read_to_string(generated_code_folder.path().join("src/port.rs"))
+        .expect("Unable to read port.rs");
+    assert!(
+        port_code.contains("pub fn pc_get("),
+        "Not found bitfield array with gaps"
+    );
+    assert!(
+        !port_code.contains("pub fn pd_get("),
+        "Found bitfield array exceeding register"
+    );
+    assert!(
+        port_code.contains("pub fn pl("),
+        "Not found bitfield following skipped array"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Size and reset value of registers are inherited from device or peripheral
+/// and access from cluster when not specified in register.
+#[test]
+fn test_register_properties_inheritance() {
+    let xml_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/register_properties.xml"
+    );
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--package-name=properties_pac",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let props_code = fs::read_to_string(generated_code_folder.path().join("src/props.rs"))
+        .expect("Unable to read props.rs");
+    let read_only_access = "crate::common::R";
+    assert!(
+        props_code
+            .match_indices(read_only_access)
+            .any(|(index, _)| {
+                !props_code[index + read_only_access.len()..].starts_with(char::is_alphanumeric)
+            }),
+        "Not found read only register with access of cluster"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    fs::create_dir_all(generated_code_folder.path().join("tests")).unwrap();
+    fs::write(
+        generated_code_folder.path().join("tests/properties.rs"),
+        r#"
+use properties_pac::*;
+
+#[test]
+fn inherited_size_and_reset_value() {
+    let ctrl: u16 = props::Ctrl::default().get_raw();
+    assert_eq!(ctrl, 0x1234);
+    let data: u8 = bytes::Data::default().get_raw();
+    assert_eq!(data, 0x5a);
+}
+"#,
+    )
+    .expect("Unable to write test of register properties");
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Registers outside of address blocks are only reported in warning mode
+#[test]
+fn test_address_block_check_warning() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/address_blocks.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--address-block-check=warning",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const ADDRESS_BLOCKS"),
+        "Not found address blocks of peripheral"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Registers outside of address blocks stop code generation in error mode
+#[test]
+#[should_panic]
+fn test_address_block_check_error() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/address_blocks.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--address-block-check=error",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+}
+
 #[test]
 fn test_license_text_option() {
     let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
@@ -71,3 +215,1674 @@ fn test_license_text_option() {
     assert!(license_path.exists(), "Not found LICENSE.txt");
     assert_files_eq("./tests/resources/LICENSE.txt", license_path);
 }
+
+/// Code generated from exported SVD is the same generated from original SVD
+#[test]
+fn test_export_svd_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let exported_svd_path = generated_code_folder.path().join("normalized.svd");
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--export-svd",
+        exported_svd_path.to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    assert!(exported_svd_path.exists(), "Not found exported SVD");
+
+    // Exported SVD shall be usable as input
+    let regenerated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        exported_svd_path.to_str().unwrap(),
+        regenerated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    assert_files_eq(
+        generated_code_folder.path().join("src/timer.rs"),
+        regenerated_code_folder.path().join("src/timer.rs"),
+    );
+    assert_files_eq(
+        generated_code_folder.path().join("src/lib.rs"),
+        regenerated_code_folder.path().join("src/lib.rs"),
+    );
+}
+
+#[test]
+fn test_cmsis_pack_input() {
+    let pack_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_pack");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        pack_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--pack-device",
+        "Simple2",
+    ];
+    main_parse_arguments(args);
+    let timer_path = generated_code_folder.path().join("src/timer.rs");
+    assert!(
+        timer_path.exists(),
+        "Not found code generated from SVD of CMSIS-Pack"
+    );
+}
+
+/// SVD file is read from zip archive of CMSIS-Pack
+#[test]
+fn test_cmsis_pack_archive_input() {
+    let svd_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let pdsc_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/simple_pack/Test.Simple_DFP.pdsc"
+    );
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let pack_path = generated_code_folder
+        .path()
+        .join("Test.Simple_DFP.1.0.0.pack");
+
+    // Pack archive contains pdsc in root folder and SVD in a subfolder
+    let pdsc = fs::read_to_string(pdsc_path)
+        .expect("Unable to read pdsc file")
+        .replace("../simple.xml", "svd/simple.xml");
+    let mut archive = zip::ZipWriter::new(fs::File::create(&pack_path).unwrap());
+    let options = zip::write::FileOptions::default();
+    archive.start_file("Test.Simple_DFP.pdsc", options).unwrap();
+    archive.write_all(pdsc.as_bytes()).unwrap();
+    archive.start_file("svd/simple.xml", options).unwrap();
+    archive.write_all(&fs::read(svd_path).unwrap()).unwrap();
+    archive.finish().unwrap();
+
+    let output_folder = generated_code_folder.path().join("output");
+    let args = [
+        "",
+        pack_path.to_str().unwrap(),
+        output_folder.to_str().unwrap(),
+        "--pack-device",
+        "Simple1",
+    ];
+    main_parse_arguments(args);
+    let timer_path = output_folder.join("src/timer.rs");
+    assert!(
+        timer_path.exists(),
+        "Not found code generated from SVD of CMSIS-Pack archive"
+    );
+}
+
+#[test]
+fn test_sha256_option() {
+    use sha2::{Digest, Sha256};
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let checksum = Sha256::digest(fs::read(xml_path).expect("Unable to read SVD file"))
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<String>();
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--sha256",
+        &checksum,
+    ];
+    main_parse_arguments(args);
+    let lib_path = generated_code_folder.path().join("src/lib.rs");
+    assert!(
+        lib_path.exists(),
+        "Not found code generated after checksum verification"
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_sha256_mismatch() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--sha256",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+    ];
+    main_parse_arguments(args);
+}
+
+#[test]
+fn test_stdin_input() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_svd2pac"))
+        .arg("-")
+        .arg(generated_code_folder.path())
+        .stdin(fs::File::open(xml_path).expect("Unable to open svd file"))
+        .status()
+        .expect("Failed to execute svd2pac");
+    assert!(status.success(), "Failed to generate code from stdin");
+    let lib_path = generated_code_folder.path().join("src/lib.rs");
+    assert!(lib_path.exists(), "Not found lib.rs");
+}
+
+#[test]
+fn test_trustzone_alias_bit_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--trustzone-alias-bit",
+        "28",
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const TIMER_S: self::Timer"),
+        "Not found secure instance"
+    );
+    assert!(
+        lib_code.contains("pub const TIMER_NS: self::Timer"),
+        "Not found non-secure instance"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Service request helpers are generated for aurix target. Generated code is compiled only by aurix tests.
+#[test]
+fn test_aurix_service_request_table() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=aurix",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let service_request_code =
+        fs::read_to_string(generated_code_folder.path().join("src/service_request.rs"))
+            .expect("Unable to read service_request.rs");
+    assert!(
+        service_request_code.contains("pub const TIMER_1: ServiceRequest"),
+        "Not found service request node of register array"
+    );
+    assert!(
+        service_request_code.contains("peripheral: \"TIMER\""),
+        "Requesting peripheral not detected"
+    );
+    assert!(
+        service_request_code.contains("pub const DMA: u8"),
+        "Not found type of service values"
+    );
+}
+
+/// Core special function registers are read from vendor extension of SVD file
+/// when the format of file is detected from its content.
+#[test]
+fn test_aurix_csfr_vendor_extension() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=aurix",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let csfr_code = fs::read_to_string(generated_code_folder.path().join("src/csfr_cpu0.rs"))
+        .expect("Unable to read csfr_cpu0.rs");
+    assert!(
+        csfr_code.contains("pub const fn biv(&self)"),
+        "Not found accessor of core register"
+    );
+}
+
+/// Core special function registers are read from separate SVD file.
+#[test]
+fn test_aurix_csfr_file_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.yaml");
+    let csfr_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_csfr.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=aurix",
+        "--aurix-csfr-file",
+        csfr_path,
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let csfr_code = fs::read_to_string(generated_code_folder.path().join("src/csfr_cpu0.rs"))
+        .expect("Unable to read csfr_cpu0.rs");
+    assert!(
+        csfr_code.contains("0xfe20"),
+        "Not found accessor of core register"
+    );
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub use csfr_cpu0 as csfr_cpu;"),
+        "Not found csfr module"
+    );
+
+    // Core registers are unrolled, each one has the description of its element
+    let csfr_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/descriptions.xml");
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=aurix",
+        "--aurix-csfr-file",
+        csfr_path,
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let csfr_code = fs::read_to_string(generated_code_folder.path().join("src/csfr_cpu0.rs"))
+        .expect("Unable to read csfr_cpu0.rs");
+    assert!(!csfr_code.contains("%s"), "Found placeholder of array");
+    assert!(
+        csfr_code.contains(r#"#[doc = "CPUx Data Protection Range 1, Lower Bound Register"]"#),
+        "Not found description of second element of register array"
+    );
+    assert!(
+        csfr_code.contains(r#"#[doc = "CPUx Safety Protection Register C"]"#),
+        "Not found description of element with dimIndex"
+    );
+}
+
+/// One feature is generated for each core of aurix device.
+/// Generated code is built on host with tracing feature, so core specific instructions are not used.
+#[test]
+fn test_aurix_core_features() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=aurix",
+        "--tracing",
+        "--package-name=aurix_pac",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let old_toml = fs::read_to_string(generated_code_folder.path().join("Cargo.toml"))
+        .expect("Unable to read Cargo.toml");
+    assert!(
+        old_toml.contains(r#"core1 = ["csfr_cpu1",]"#),
+        "Not found feature of core 1"
+    );
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("compile_error!(\"Only one of features core0, core1"),
+        "Not found check of multiple core features"
+    );
+
+    // Build with core 1 selected and use addresses of core local view
+    let mut parsed_toml = old_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("tracing");
+    default_table.push("core1");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        generated_code_folder.path().join("Cargo.toml"),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+    fs::create_dir_all(generated_code_folder.path().join("src/bin")).unwrap();
+    fs::write(
+        generated_code_folder.path().join("src/bin/core_local.rs"),
+        r#"
+use aurix_pac::{core_local, Core};
+
+const _: () = assert!(core_local::CORE_ID == 1);
+const _: () = assert!(core_local::Core::DSPR == 0x6000_0000);
+const _: () = assert!(Core::<0>::PSPR == 0x7010_0000);
+const _: () = assert!(core_local::Core::to_global(core_local::DSPR + 0x100) == 0x6000_0100);
+
+fn main() {}
+"#,
+    )
+    .expect("Unable to write binary using core local view");
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Rust enums are generated only for complete enumerations when requested
+#[test]
+fn test_rust_enums_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--rust-enums",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub mod enums"),
+        "Not found module of Rust enums"
+    );
+    assert!(
+        timer_code.contains("pub enum Run"),
+        "Not found Rust enum of complete enumeration"
+    );
+    assert!(
+        !timer_code.contains("BOTH = 2,"),
+        "Rust enum generated for incomplete enumeration"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Only bits of read-write bitfields are verified after write
+#[test]
+fn test_write_verify_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--write-verify",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let common_code = fs::read_to_string(generated_code_folder.path().join("src/common.rs"))
+        .expect("Unable to read common.rs");
+    assert!(
+        common_code.contains("pub unsafe fn write_verify"),
+        "Not found write_verify function"
+    );
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("impl crate::sealed::VerifyWrite for BitfieldReg_SPEC"),
+        "Not found verify mask of read-write register"
+    );
+
+    // Check read back of register emulated by tracing functions
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    fs::create_dir_all(generated_code_folder.path().join("tests")).unwrap();
+    fs::write(
+        generated_code_folder.path().join("tests/write_verify.rs"),
+        r#"
+use std::cell::Cell;
+use test_pac::*;
+
+thread_local! {
+    static REGISTER: Cell<u64> = Cell::new(0);
+    static READ_BACK: Cell<fn(u64) -> u64> = Cell::new(|value| value);
+}
+
+fn read_fn(_addr: usize, _len: usize) -> u64 {
+    READ_BACK.with(|read_back| read_back.get()(REGISTER.with(|register| register.get())))
+}
+fn write_fn(_addr: usize, _len: usize, val: u64) {
+    REGISTER.with(|register| register.set(val));
+}
+
+#[test]
+fn write_verify() {
+    tracing::set_read_fn(read_fn).unwrap();
+    tracing::set_write_fn(write_fn).unwrap();
+    let value = timer::BitfieldReg::default().bitfieldrw().set(0x3);
+    assert_eq!(unsafe { TIMER.bitfield_reg().write_verify(value) }, Ok(()));
+
+    // Bits of write-only bitfields are not compared
+    READ_BACK.with(|read_back| read_back.set(|value| value & !0xC2));
+    let value = value.bitfieldw().set(0x3).boolw().set(true);
+    assert_eq!(unsafe { TIMER.bitfield_reg().write_verify(value) }, Ok(()));
+
+    // Read-write bitfield is not updated by write
+    READ_BACK.with(|read_back| read_back.set(|value| value & !0xF00));
+    let value = timer::BitfieldReg::default().bitfieldrw().set(0x5);
+    let error = unsafe { TIMER.bitfield_reg().write_verify(value) }.unwrap_err();
+    assert_eq!(error.written & 0xF00, 0x500);
+    assert_ne!(error.read & 0xF00, 0x500);
+    assert_eq!(error.mask & 0xF00, 0xF00);
+}
+"#,
+    )
+    .expect("Unable to write test of write_verify");
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Peripheral instances and absolute addresses are not generated
+#[test]
+fn test_offset_only_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--offset-only",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const unsafe fn from_ptr"),
+        "Not found from_ptr constructor"
+    );
+    assert!(
+        !lib_code.contains("pub const TIMER:") && !lib_code.contains("BASE_ADDR"),
+        "Found absolute address of peripheral"
+    );
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        !timer_code.contains("pub const ADDRESS"),
+        "Found absolute address of register"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Instances of derived peripherals with same registers use the struct of their parent
+#[test]
+fn test_derived_peripheral_shares_struct() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = ["", xml_path, generated_code_folder.path().to_str().unwrap()];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const DERIVEDPERIPHERAL: self::P33 ="),
+        "Derived peripheral doesn't use struct of parent"
+    );
+    assert!(
+        !lib_code.contains("pub struct DerivedPeripheral"),
+        "Found struct of derived peripheral"
+    );
+    // Feature of derived peripheral enables the module of its parent
+    let toml = fs::read_to_string(generated_code_folder.path().join("Cargo.toml"))
+        .expect("Unable to read Cargo.toml");
+    assert!(
+        toml.contains(r#"derivedperipheral = ["p33"]"#),
+        "Feature of derived peripheral doesn't enable its parent"
+    );
+    set_default_features(generated_code_folder.path(), &["derivedperipheral"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Peripherals and clusters with identical layout share their structs
+#[test]
+fn test_dedup_layouts_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/dedup_layouts.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--dedup-layouts",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    assert!(
+        !generated_code_folder.path().join("src/uart1.rs").exists(),
+        "Found module of peripheral with same layout"
+    );
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const UART1: self::Uart0"),
+        "Peripheral with same layout doesn't use struct of first one"
+    );
+    let dma_code = fs::read_to_string(generated_code_folder.path().join("src/dma.rs"))
+        .expect("Unable to read dma.rs");
+    assert!(
+        dma_code.contains("pub struct Ch0") && !dma_code.contains("pub struct Ch1"),
+        "Cluster with same layout doesn't use struct of first one"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Accessors get the selected inline attribute
+#[test]
+fn test_inline_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--inline",
+        "hint",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("#[inline]") && !timer_code.contains("#[inline(always)]"),
+        "Accessors don't use selected inline attribute"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Read and written values have distinct types with only readable or writable bitfields
+#[test]
+fn test_split_read_write_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--split-read-write",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let common_code = fs::read_to_string(generated_code_folder.path().join("src/common.rs"))
+        .expect("Unable to read common.rs");
+    assert!(
+        common_code.contains("pub struct ReadValue")
+            && common_code.contains("pub struct WriteValue"),
+        "Types of read and written values not generated"
+    );
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("ReadValue<BitfieldReg_SPEC>")
+            && timer_code.contains("WriteValue<BitfieldReg_SPEC>"),
+        "Bitfield accessors of read and written values not generated"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::*;
+
+#[test]
+fn split_read_write() {
+    init_register_memory();
+    unsafe {
+        TIMER
+            .bitfield_reg()
+            .write_w(|w| w.bitfieldrw(0x5).bitfieldw(0x2).boolrw(true));
+        let r = TIMER.bitfield_reg().read_r();
+        assert_eq!(r.bitfieldrw(), 0x5);
+        assert!(r.boolrw());
+        assert_eq!(r.get_raw(), 0x584);
+
+        TIMER
+            .bitfield_reg()
+            .modify_rw(|r, w| w.bitfieldrw(r.bitfieldrw() + 1).boolrw(!r.boolrw()));
+        let r = TIMER.bitfield_reg().read_r();
+        assert_eq!(r.bitfieldrw(), 0x6);
+        assert!(!r.boolrw());
+
+        TIMER.bitfield_reg().write_w(|w| w.set_raw(0x300));
+        assert_eq!(TIMER.bitfield_reg().read_r().bitfieldrw(), 0x3);
+    }
+}
+"#,
+    );
+}
+
+/// svd2rust-style access functions are generated in compat module
+#[test]
+fn test_svd2rust_compat_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--svd2rust-compat",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub mod compat;"),
+        "compat module not declared"
+    );
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("compat::R<BitfieldReg_SPEC>")
+            && timer_code.contains("compat::W<BitfieldReg_SPEC>"),
+        "svd2rust-style bitfield accessors not generated"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::timer::bitfield_reg::BitfieldEnumerated;
+use test_pac::*;
+
+#[test]
+fn svd2rust_compat() {
+    init_register_memory();
+    let reg = TIMER.bitfield_reg().compat();
+    unsafe {
+        reg.write(|w| w.bitfieldrw().bits(0x5).boolrw().set_bit());
+        let r = reg.read();
+        assert_eq!(r.bitfieldrw().bits(), 0x5);
+        assert!(r.boolrw().bit_is_set());
+
+        reg.modify(|r, w| {
+            w.boolrw()
+                .bit(!r.boolrw().bit())
+                .bitfieldenumerated()
+                .variant(BitfieldEnumerated::GPIOA_1)
+        });
+        let r = reg.read();
+        assert!(r.boolrw().bit_is_clear());
+        assert_eq!(r.bitfieldrw().bits(), 0x5);
+        assert!(r.bitfieldenumerated().variant() == BitfieldEnumerated::GPIOA_1);
+
+        reg.write_with_zero(|w| w.bits(0x300));
+        assert_eq!(reg.read().bits(), 0x300);
+    }
+}
+"#,
+    );
+}
+
+/// chiptool-style register blocks are returned by regs() of peripherals
+#[test]
+fn test_chiptool_compat_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--chiptool-compat",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub mod chiptool;"),
+        "chiptool module not declared"
+    );
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub const unsafe fn regs(self)")
+            && timer_code.contains("chiptool::Value<BitfieldReg_SPEC>"),
+        "chiptool-style accessors not generated"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::*;
+
+#[test]
+fn chiptool_compat() {
+    init_register_memory();
+    let regs = unsafe { TIMER.regs() };
+    regs.bitfield_reg().write(|w| {
+        w.set_bitfieldrw(0x5);
+        w.set_boolrw(true);
+    });
+    let value = regs.bitfield_reg().read();
+    assert_eq!(value.bitfieldrw(), 0x5);
+    assert!(value.boolrw());
+
+    let old = regs.bitfield_reg().modify(|w| {
+        let old = w.bitfieldrw();
+        w.set_bitfieldrw(old + 1);
+        old
+    });
+    assert_eq!(old, 0x5);
+    assert_eq!(regs.bitfield_reg().read().bitfieldrw(), 0x6);
+    assert!(regs.bitfield_reg().read().boolrw());
+    assert_eq!(regs.bitfield_reg().as_ptr() as usize, TIMER.bitfield_reg().addr());
+}
+"#,
+    );
+}
+
+/// Non-secure instances are generated only for peripherals in non-secure SAU regions
+#[test]
+fn test_trustzone_sau_regions() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/trustzone.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--trustzone-alias-bit",
+        "28",
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const UART_NS: self::Uart"),
+        "Not found non-secure instance of peripheral in non-secure region"
+    );
+    assert!(
+        lib_code.contains("pub const CRYPTO_S: self::Crypto"),
+        "Not found secure instance of peripheral in secure region"
+    );
+    assert!(
+        !lib_code.contains("CRYPTO_NS"),
+        "Found non-secure instance of peripheral in secure region"
+    );
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Builder-style setters are generated for writable bitfields
+#[test]
+fn test_with_setters_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--with-setters",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub fn with_boolw(") && !timer_code.contains("pub fn with_boolr("),
+        "Builder-style setters not generated only for writable bitfields"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::timer::bitfield_reg::FieldArray;
+use test_pac::*;
+
+#[test]
+fn with_setters() {
+    let value = timer::BitfieldReg::default()
+        .with_boolw(true)
+        .with_bitfieldrw(0x5)
+        .with_fieldarray(1, FieldArray::FALLING);
+    let expected = timer::BitfieldReg::default()
+        .boolw()
+        .set(true)
+        .bitfieldrw()
+        .set(0x5)
+        .fieldarray(1)
+        .set(FieldArray::FALLING);
+    assert_eq!(value.get_raw(), expected.get_raw());
+    assert_eq!(value.bitfieldrw().get(), 0x5);
+}
+"#,
+    );
+}
+
+/// read() is safe only for registers without read side effects
+#[test]
+fn test_safe_read_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--safe-read",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("BitfieldReg_SPEC, crate::common::SafeRW>"),
+        "Register without read side effects doesn't have safe read"
+    );
+    assert!(
+        timer_code.contains("PrescaleRd_SPEC, crate::common::R>")
+            && timer_code.contains("PrescaleWr_SPEC, crate::common::W>"),
+        "Register with read side effects or write-only register has safe read"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::*;
+
+#[test]
+fn safe_read() {
+    init_register_memory();
+    unsafe {
+        TIMER
+            .bitfield_reg()
+            .write(timer::BitfieldReg::default().bitfieldrw().set(0x5));
+    }
+    // No unsafe block required
+    assert_eq!(TIMER.bitfield_reg().read().bitfieldrw().get(), 0x5);
+    let _ = unsafe { TIMER.prescale_rd().read() };
+}
+"#,
+    );
+}
+
+/// Peripherals can be taken only once and stealing them is unsafe
+#[test]
+fn test_peripherals_singleton_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=cortex-m",
+        "--peripherals-singleton",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("static PERIPHERALS_TAKEN")
+            && lib_code.contains("pub unsafe fn steal() -> Self"),
+        "Singleton peripherals not generated"
+    );
+    set_default_features(
+        generated_code_folder.path(),
+        &["all", "tracing", "critical-section"],
+    );
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::*;
+
+struct HostCriticalSection;
+critical_section::set_impl!(HostCriticalSection);
+unsafe impl critical_section::Impl for HostCriticalSection {
+    unsafe fn acquire() {}
+    unsafe fn release(_: ()) {}
+}
+
+#[test]
+fn peripherals_singleton() {
+    let peripherals = Peripherals::take().unwrap();
+    assert!(Peripherals::take().is_none());
+    assert_eq!(peripherals.TIMER.base_addr(), TIMER.base_addr());
+    let _ = unsafe { Peripherals::steal() };
+    assert!(Peripherals::take().is_none());
+}
+"#,
+    );
+}
+
+/// Plain structs with values of writable bitfields are generated for registers
+#[test]
+fn test_fields_struct_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--fields-struct",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub struct BitfieldRegFields")
+            && timer_code.contains("pub unsafe fn write_fields("),
+        "Fields struct not generated"
+    );
+    assert!(
+        !timer_code.contains("pub struct PrescaleRdFields"),
+        "Fields struct generated for read-only register"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::timer::bitfield_reg::BitfieldEnumerated;
+use test_pac::*;
+
+#[test]
+fn fields_struct() {
+    init_register_memory();
+    let fields = timer::BitfieldRegFields {
+        boolrw: true,
+        bitfieldrw: 0x5,
+        bitfieldenumerated: BitfieldEnumerated::GPIOA_1,
+        ..Default::default()
+    };
+    unsafe { TIMER.bitfield_reg().write_fields(fields) };
+    let value = unsafe { TIMER.bitfield_reg().read() };
+    assert!(value.boolrw().get());
+    assert_eq!(value.bitfieldrw().get(), 0x5);
+    assert!(value.bitfieldenumerated().get() == BitfieldEnumerated::GPIOA_1);
+
+    // Conversion from register value and back doesn't change bitfields
+    let round_trip = timer::BitfieldRegFields::from(value);
+    assert!(round_trip == fields);
+    assert_eq!(timer::BitfieldReg::from(round_trip).get_raw(), value.get_raw());
+    assert!(timer::BitfieldRegFields::default() == timer::BitfieldReg::RESET.into());
+}
+"#,
+    );
+}
+
+/// Registers at the same address without alternateRegister are documented as aliased
+#[test]
+fn test_aliased_registers() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--aliased-register-check=relaxed",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("it shares its address with `capture()`")
+            && timer_code.contains("it shares its address with `match()`"),
+        "Aliased registers not documented"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+#[test]
+#[should_panic]
+fn test_aliased_registers_error() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--aliased-register-check=error",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+}
+
+/// Plain structs with values of all bitfields are generated for registers
+#[test]
+fn test_unpacked_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--unpacked",
+        "--tracing",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub struct BitfieldRegUnpacked")
+            && timer_code.contains("From<BitfieldRegUnpacked> for BitfieldReg"),
+        "Unpacked struct not generated"
+    );
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    assert_generated_test(
+        generated_code_folder,
+        r#"
+use test_pac::timer::bitfield_reg::BitfieldEnumerated;
+use test_pac::*;
+
+#[test]
+fn unpacked() {
+    init_register_memory();
+    let unpacked = timer::BitfieldRegUnpacked {
+        boolr: true,
+        boolrw: true,
+        bitfieldrw: 0x5,
+        bitfieldenumerated: BitfieldEnumerated::GPIOA_1,
+        ..Default::default()
+    };
+    unsafe { TIMER.bitfield_reg().write(unpacked.into()) };
+    let snapshot: timer::BitfieldRegUnpacked = unsafe { TIMER.bitfield_reg().read() }.into();
+    assert!(snapshot == unpacked);
+
+    // Round trip through register value keeps all bitfields
+    let value = timer::BitfieldReg::from(snapshot);
+    assert!(value.boolr().get());
+    assert_eq!(value.bitfieldrw().get(), 0x5);
+    assert!(timer::BitfieldRegUnpacked::from(value) == unpacked);
+    assert!(timer::BitfieldRegUnpacked::default() == timer::BitfieldReg::RESET.into());
+}
+"#,
+    );
+}
+
+/// Arbitrary implementations of register values and sample property test are generated
+#[test]
+fn test_arbitrary_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--arbitrary",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    let common_code = fs::read_to_string(generated_code_folder.path().join("src/common.rs"))
+        .expect("Unable to read common.rs");
+    assert!(
+        common_code.contains("arbitrary::Arbitrary<'a> for RegValueT<T>")
+            && common_code.contains("proptest::arbitrary::Arbitrary for RegValueT<T>"),
+        "Arbitrary implementations not generated"
+    );
+    let test_code = fs::read_to_string(
+        generated_code_folder
+            .path()
+            .join("tests/register_values.rs"),
+    )
+    .expect("Unable to read tests/register_values.rs");
+    assert!(
+        test_code.contains("fn timer_bitfield_reg(value: timer::BitfieldReg)"),
+        "Property test of register not generated"
+    );
+
+    // Run property test with all peripherals
+    let old_toml = fs::read_to_string(Path::new(&generated_code_folder.path().join("Cargo.toml")))
+        .expect("Unable to read toml file");
+    let mut parsed_toml = old_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("arbitrary");
+    default_table.push("proptest");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        Path::new(Path::new(&generated_code_folder.path().join("Cargo.toml"))),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Test generation driven by configuration file
+#[test]
+fn test_config_file() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    let config_path = destination_folder.join("svd2pac.toml");
+    let config = format!(
+        r#"
+register-description-file = {xml_path:?}
+destination-folder = {destination_folder:?}
+
+[options]
+tracing = true
+package_name = "config-pac"
+
+[renames]
+"TIMER.BITFIELD_REG.BoolR" = "BoolRead"
+
+[filter]
+exclude = ["FOO", "Escape*"]
+
+[features]
+timers = ["TIMER"]
+"#
+    );
+    fs::write(&config_path, config).expect("Unable to write configuration file");
+    let args = ["", "--config", config_path.to_str().unwrap()];
+    main_parse_arguments(args);
+
+    let cargo_toml = fs::read_to_string(destination_folder.join("Cargo.toml"))
+        .expect("Unable to read toml file");
+    assert!(
+        cargo_toml.contains(r#"name = "config-pac""#),
+        "Package name of configuration file not used"
+    );
+    assert!(
+        cargo_toml.contains("tracing = "),
+        "Tracing option of configuration file not used"
+    );
+    assert!(
+        cargo_toml.contains(r#"timers = ["timer",]"#),
+        "Feature group not generated"
+    );
+    assert!(
+        !cargo_toml.contains("\nfoo = ") && !destination_folder.join("src/foo.rs").exists(),
+        "Excluded peripheral generated"
+    );
+    let timer_code = fs::read_to_string(destination_folder.join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub fn boolread(") && !timer_code.contains("pub fn boolr("),
+        "Bitfield not renamed"
+    );
+
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Test regular expression based rename rules
+#[test]
+fn test_rename_rules() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let rules_path = generated_code_folder.path().join("rename_rules.toml");
+    fs::write(
+        &rules_path,
+        r#"
+[[rules]]
+items = "fields"
+peripheral = "TIMER"
+pattern = "^Bool(R|W)$"
+replacement = "Flag$1"
+
+# Peripheral used by derivedFrom of other peripheral
+[[rules]]
+items = "peripherals"
+pattern = "^P33$"
+replacement = "PORT33"
+"#,
+    )
+    .expect("Unable to write rename rules file");
+    let args = [
+        "",
+        "--rename-rules",
+        rules_path.to_str().unwrap(),
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("pub fn flagr(")
+            && timer_code.contains("pub fn flagw(")
+            && timer_code.contains("pub fn boolrw("),
+        "Bitfields not renamed by rules"
+    );
+    let cargo_toml = fs::read_to_string(generated_code_folder.path().join("Cargo.toml"))
+        .expect("Unable to read toml file");
+    assert!(
+        cargo_toml.contains("\nport33 = "),
+        "Peripheral not renamed by rules"
+    );
+
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Test `--strip-peripheral-prefix` option
+#[test]
+fn test_strip_peripheral_prefix() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/strip_prefix.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--strip-peripheral-prefix",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let uart_code = fs::read_to_string(generated_code_folder.path().join("src/uart0.rs"))
+        .expect("Unable to read uart0.rs");
+    // Prefix of peripheral with and without instance number
+    assert!(
+        uart_code.contains("pub const fn ctrl(") && uart_code.contains("pub const fn ctrl2("),
+        "Peripheral prefix not stripped"
+    );
+    // Prefix of other peripherals is kept
+    assert!(
+        uart_code.contains("pub const fn timer_ctrl("),
+        "Prefix of other peripheral stripped"
+    );
+    // Names colliding after stripping are kept
+    assert!(
+        uart_code.contains("pub const fn uart_stat(") && uart_code.contains("pub const fn stat("),
+        "Colliding name stripped"
+    );
+
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Test `--workspace` option
+#[test]
+fn test_workspace() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    // Peripherals of feature group share a crate
+    let config_path = destination_folder.join("svd2pac.toml");
+    fs::write(&config_path, "[features]\ntimers = [\"TIMER\", \"FOO\"]\n")
+        .expect("Unable to write configuration file");
+    let args = [
+        "",
+        "--workspace",
+        "--config",
+        config_path.to_str().unwrap(),
+        xml_path,
+        destination_folder.to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    for member in ["timers", "uart", "p33"] {
+        let member_folder = destination_folder.join("peripherals").join(member);
+        assert!(
+            member_folder.join("Cargo.toml").exists()
+                && member_folder.join("src/common.rs").exists(),
+            "Crate {member} not generated"
+        );
+    }
+    assert!(
+        destination_folder
+            .join("peripherals/timers/src/foo.rs")
+            .exists(),
+        "Peripheral of feature group not in crate of group"
+    );
+    // DerivedTest uses structs of TIMER module
+    assert!(
+        destination_folder
+            .join("peripherals/timers/src/derivedtest.rs")
+            .exists(),
+        "Peripheral using structs of other module not in crate of that module"
+    );
+    assert!(
+        !destination_folder.join("src/timer.rs").exists(),
+        "Peripheral module generated in main crate"
+    );
+    // Derived peripheral is in crate of its module
+    let cargo_toml = fs::read_to_string(destination_folder.join("Cargo.toml"))
+        .expect("Unable to read toml file");
+    assert!(
+        cargo_toml.contains(
+            r#"derivedperipheral = ["p33", "dep:test_pac-p33", "test_pac-p33/derivedperipheral"]"#
+        ),
+        "Feature of derived peripheral doesn't enable its crate"
+    );
+
+    // Build main crate with all peripherals
+    let mut parsed_toml = cargo_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("debug-fields");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        destination_folder.join("Cargo.toml"),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+
+    assert_cargo_build(generated_code_folder);
+}
+
+#[test]
+fn test_single_file() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    let args = [
+        "",
+        "--single-file",
+        xml_path,
+        destination_folder.to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let source_files: Vec<_> = fs::read_dir(destination_folder.join("src"))
+        .expect("Unable to read src folder")
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(source_files, ["lib.rs"], "Module files generated");
+    let lib_code =
+        fs::read_to_string(destination_folder.join("src/lib.rs")).expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub mod common {") && lib_code.contains("pub mod timer {"),
+        "Modules not in lib.rs"
+    );
+
+    // Build with all peripherals
+    let cargo_toml = fs::read_to_string(destination_folder.join("Cargo.toml"))
+        .expect("Unable to read toml file");
+    let mut parsed_toml = cargo_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("debug-fields");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        destination_folder.join("Cargo.toml"),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+
+    assert_cargo_build(generated_code_folder);
+}
+
+#[test]
+fn test_modules_only() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    // Package with features and dependencies required by modules
+    main_parse_arguments(["", xml_path, destination_folder.to_str().unwrap()]);
+    fs::remove_dir_all(destination_folder.join("src")).expect("Unable to remove src folder");
+
+    let modules_folder = destination_folder.join("src/pac");
+    let args = [
+        "",
+        "--modules-only",
+        "crate::pac",
+        xml_path,
+        modules_folder.to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    assert!(
+        modules_folder.join("mod.rs").exists() && modules_folder.join("timer.rs").exists(),
+        "Modules not generated"
+    );
+    assert!(
+        !modules_folder.join("Cargo.toml").exists()
+            && !modules_folder.join("LICENSE.txt").exists()
+            && !modules_folder.join("src").exists(),
+        "Package files generated"
+    );
+    let timer_code =
+        fs::read_to_string(modules_folder.join("timer.rs")).expect("Unable to read timer.rs");
+    assert!(
+        timer_code.contains("crate::pac::common::") && !timer_code.contains(" crate::common::"),
+        "Paths not relative to module"
+    );
+    fs::write(
+        destination_folder.join("src/lib.rs"),
+        "#![no_std]\npub mod pac;\n",
+    )
+    .expect("Unable to write lib.rs");
+
+    // Build with all peripherals
+    let cargo_toml = fs::read_to_string(destination_folder.join("Cargo.toml"))
+        .expect("Unable to read toml file");
+    let mut parsed_toml = cargo_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("debug-fields");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        destination_folder.join("Cargo.toml"),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+
+    assert_cargo_build(generated_code_folder);
+}
+
+#[test]
+fn test_diff_option() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    main_parse_arguments(["", xml_path, destination_folder.to_str().unwrap()]);
+    let timer_code = fs::read_to_string(destination_folder.join("src/timer.rs"))
+        .expect("Unable to read timer.rs");
+
+    // Destination folder is not changed
+    let args = [
+        "",
+        "--diff=lines",
+        "--rust-enums",
+        xml_path,
+        destination_folder.to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+    assert_eq!(
+        fs::read_to_string(destination_folder.join("src/timer.rs")).unwrap(),
+        timer_code,
+        "Destination folder changed by diff"
+    );
+
+    // Missing destination folder is not created
+    let missing_folder = destination_folder.join("missing");
+    let args = ["", "--diff", xml_path, missing_folder.to_str().unwrap()];
+    main_parse_arguments(args);
+    assert!(
+        !missing_folder.exists(),
+        "Destination folder created by diff"
+    );
+}
+
+/// Regenerated code is identical to code generated before
+#[test]
+fn test_verify() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path().to_str().unwrap();
+    main_parse_arguments(["", "--tracing", xml_path, destination_folder]);
+    main_parse_arguments(["", "--verify", "--tracing", xml_path, destination_folder]);
+}
+
+#[test]
+#[should_panic]
+fn test_verify_changed_code() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    main_parse_arguments(["", xml_path, destination_folder.to_str().unwrap()]);
+    let timer_path = destination_folder.join("src/timer.rs");
+    let timer_code = fs::read_to_string(&timer_path).expect("Unable to read timer.rs");
+    fs::write(&timer_path, timer_code + "\n// Changed by hand\n").unwrap();
+    main_parse_arguments([
+        "",
+        "--verify",
+        xml_path,
+        destination_folder.to_str().unwrap(),
+    ]);
+}
+
+#[test]
+fn test_post_hook() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    for stage in ["before-rustfmt", "after-rustfmt"] {
+        let post_hook_stage = format!("--post-hook-stage={stage}");
+        let args = [
+            "",
+            "--post-hook",
+            "echo done > hook.txt",
+            post_hook_stage.as_str(),
+            xml_path,
+            destination_folder.to_str().unwrap(),
+        ];
+        main_parse_arguments(args);
+        let hook_path = destination_folder.join("hook.txt");
+        assert!(
+            hook_path.exists(),
+            "Post hook not executed in destination folder"
+        );
+        fs::remove_file(hook_path).unwrap();
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_failing_post_hook() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--post-hook",
+        "exit 1",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+}
+
+/// Test that renames of configuration file update `derivedFrom` of derived items
+#[test]
+fn test_config_file_rename_base() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    let config_path = destination_folder.join("svd2pac.toml");
+    let config = format!(
+        r#"
+register-description-file = {xml_path:?}
+destination-folder = {destination_folder:?}
+
+[renames]
+"P33" = "PORT33"
+"TIMER" = "TIM"
+"DerivedTest.BaseRegister" = "BaseReg"
+"#
+    );
+    fs::write(&config_path, config).expect("Unable to write configuration file");
+    let args = ["", "--config", config_path.to_str().unwrap()];
+    main_parse_arguments(args);
+
+    let lib_code =
+        fs::read_to_string(destination_folder.join("src/lib.rs")).expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub const DERIVEDPERIPHERAL: self::Port33"),
+        "Derived peripheral not instance of renamed base peripheral"
+    );
+
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Test that filter of configuration file doesn't remove base of a derived peripheral
+#[test]
+#[should_panic]
+fn test_config_file_filter_base() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path();
+    let config_path = destination_folder.join("svd2pac.toml");
+    let config = format!(
+        r#"
+register-description-file = {xml_path:?}
+destination-folder = {destination_folder:?}
+
+[filter]
+exclude = ["P33"]
+"#
+    );
+    fs::write(&config_path, config).expect("Unable to write configuration file");
+    let args = ["", "--config", config_path.to_str().unwrap()];
+    main_parse_arguments(args);
+}
+
+/// Register description file in current directory named like a subcommand is not handled as subcommand
+#[test]
+fn test_file_named_like_subcommand() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    fs::copy(xml_path, "info").expect("Unable to copy register description file");
+    main_parse_arguments(["", "info", generated_code_folder.path().to_str().unwrap()]);
+    fs::remove_file("info").expect("Unable to remove register description file");
+    assert!(
+        generated_code_folder.path().join("src/lib.rs").exists(),
+        "Code not generated from file named like subcommand"
+    );
+}
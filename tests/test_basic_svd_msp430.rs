@@ -0,0 +1,54 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test msp430 target code generation.
+#[test]
+fn compile_generated_msp430() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=msp430",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub enum Interrupt "),
+        "Not found interrupt enumeration"
+    );
+    assert_cargo_build(generated_code_folder);
+}
+
+/// Registers without size in SVD are 16 bit wide for msp430 target.
+#[test]
+fn test_msp430_default_register_size() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/no_register_size.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=msp430",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let port_code = fs::read_to_string(generated_code_folder.path().join("src/port.rs"))
+        .expect("Unable to read port.rs");
+    assert!(
+        port_code.contains("type DataType = u16;"),
+        "Register without size is not 16 bit wide"
+    );
+}
@@ -32,6 +32,8 @@ fn compile_generated_tracing() {
     let mut default_table = Array::new();
     default_table.push("all");
     default_table.push("tracing");
+    default_table.push("hook");
+    default_table.push("tracing_global");
     parsed_toml["features"]["default"] = value(default_table);
     parsed_toml["bin"] = array();
     let bin_array = parsed_toml["bin"].as_array_of_tables_mut().unwrap();
@@ -65,3 +67,150 @@ fn compile_generated_tracing() {
     assert_cargo_build(generated_code_folder);
     assert_cargo_test(generated_test_folder);
 }
+
+/// Test mock register file used with mock feature.
+#[test]
+fn test_generated_mock() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--tracing",
+    ];
+
+    main_parse_arguments(args);
+
+    let mock_code = fs::read_to_string(generated_code_folder.path().join("src/mock.rs"))
+        .expect("Unable to read mock.rs");
+    assert!(
+        mock_code.contains("address: 0x40010000,"),
+        "Register of TIMER not in mock register file"
+    );
+
+    // Enable mock feature by default
+    set_default_features(generated_code_folder.path(), &["all", "mock"]);
+
+    fs_extra::dir::copy(
+        "./tests/resources/project_files_mock",
+        generated_code_folder.path(),
+        &CopyOptions::new().content_only(true).overwrite(true),
+    )
+    .expect("Failed to copy required files to build cargo project");
+
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Test generated unit test skeletons with mocked registers
+#[test]
+fn test_generated_test_skeletons() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--tracing",
+        "--test-skeletons",
+    ];
+
+    main_parse_arguments(args);
+
+    let test_code = fs::read_to_string(generated_code_folder.path().join("tests/timer.rs"))
+        .expect("Unable to read tests/timer.rs");
+    assert!(
+        test_code.contains("unsafe { TIMER.dump() }"),
+        "TIMER not used in test skeleton"
+    );
+    let uart_code = fs::read_to_string(generated_code_folder.path().join("tests/uart.rs"))
+        .expect("Unable to read tests/uart.rs");
+    assert!(
+        uart_code.contains("unsafe { UART[0].dump() }"),
+        "Peripheral array not used in test skeleton"
+    );
+
+    // Enable mock feature by default
+    let old_toml = fs::read_to_string(Path::new(&generated_code_folder.path().join("Cargo.toml")))
+        .expect("Unable to read toml file");
+    let mut parsed_toml = old_toml
+        .parse::<Document>()
+        .expect("Unable to parse toml file");
+    let mut default_table = Array::new();
+    default_table.push("all");
+    default_table.push("mock");
+    parsed_toml["features"]["default"] = value(default_table);
+    fs::write(
+        Path::new(Path::new(&generated_code_folder.path().join("Cargo.toml"))),
+        parsed_toml.to_string(),
+    )
+    .expect("Unable to write toml file");
+
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Test that atomic modify of Cortex-M is traced as read and write.
+#[test]
+fn test_tracing_cortex_m_modify_atomic() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=cortex-m",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--tracing",
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all", "tracing"]);
+    fs_extra::dir::copy(
+        "./tests/resources/project_files_tracing_cortex_m",
+        generated_code_folder.path(),
+        &CopyOptions::new().content_only(true).overwrite(true),
+    )
+    .expect("Failed to copy required files to build cargo project");
+
+    assert_cargo_test(generated_code_folder);
+}
+
+/// Test generated simulator crate
+#[test]
+fn test_generated_simulator() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--tracing",
+        "--simulator",
+    ];
+
+    main_parse_arguments(args);
+
+    let sim_code = fs::read_to_string(generated_code_folder.path().join("sim/src/lib.rs"))
+        .expect("Unable to read sim/src/lib.rs");
+    assert!(sim_code.contains("pub struct Timer"), "TIMER not simulated");
+    assert!(
+        sim_code.contains("pub struct Uart"),
+        "Peripheral array not simulated"
+    );
+
+    fs_extra::dir::copy(
+        "./tests/resources/project_files_sim",
+        generated_code_folder.path().join("sim"),
+        &CopyOptions::new().content_only(true).overwrite(true),
+    )
+    .expect("Failed to copy required files to build cargo project");
+
+    assert_cargo_test_in(generated_code_folder, "sim");
+}
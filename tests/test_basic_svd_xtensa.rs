@@ -0,0 +1,31 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test xtensa target code generation.
+#[test]
+fn compile_generated_xtensa() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=xtensa",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_code = fs::read_to_string(generated_code_folder.path().join("src/lib.rs"))
+        .expect("Unable to read lib.rs");
+    assert!(
+        lib_code.contains("pub enum InterruptLevel"),
+        "Not found interrupt levels"
+    );
+    assert_cargo_build(generated_code_folder);
+}
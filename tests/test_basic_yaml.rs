@@ -0,0 +1,27 @@
+mod common;
+use common::*;
+use std::env;
+use svd2pac::main_parse_arguments;
+
+/// Test code generation from YAML device description.
+#[test]
+fn compile_generated_yaml() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.yaml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--license-file",
+        "./tests/resources/LICENSE.txt",
+    ];
+    main_parse_arguments(args);
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+
+    let lib_path = generated_code_folder.path().join("src/timer.rs");
+    assert!(lib_path.exists(), "Not found module of YAML peripheral");
+    assert_cargo_build(generated_code_folder);
+}
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test code generation with a target definition loaded at runtime.
+#[test]
+fn generate_custom_target() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let target = concat!(
+        "--target=custom:",
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/custom_target"
+    );
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        target,
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let src_path = generated_code_folder.path().join("src");
+    assert!(
+        src_path.join("lib.rs").exists(),
+        "Templates of base target not used"
+    );
+    let core_info = fs::read_to_string(src_path.join("core_info.rs"))
+        .expect("Additional file of target definition not generated");
+    assert!(
+        core_info.contains(r#"pub const CORE_NAME: &str = "SimpleCore";"#),
+        "Context of target definition not available in templates"
+    );
+}
+
+/// Registers without size in SVD use default register size of target definition
+#[test]
+fn custom_target_default_register_size() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/no_register_size.xml");
+    let target = concat!(
+        "--target=custom:",
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/custom_target"
+    );
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        target,
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+
+    let port_code = fs::read_to_string(generated_code_folder.path().join("src/port.rs"))
+        .expect("Unable to read port.rs");
+    assert!(
+        port_code.contains("type DataType = u32;"),
+        "Register without size doesn't use size of target definition"
+    );
+}
+
+/// Target definition that doesn't exist is reported as error
+#[test]
+#[should_panic]
+fn missing_custom_target() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        "--target=custom:./not_existing_target",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+    ];
+    main_parse_arguments(args);
+}
+
+/// Variables of template context file are available in templates of target definition
+#[test]
+fn custom_target_template_context() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let target = concat!(
+        "--target=custom:",
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_svd/custom_target"
+    );
+    for context_file in ["template_context.json", "template_context.toml"] {
+        let template_context = format!(
+            "--template-context={}/test_svd/{context_file}",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        // Temp folder that should be deleted in case of test success.
+        let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+        let args = [
+            "",
+            target,
+            template_context.as_str(),
+            xml_path,
+            generated_code_folder.path().to_str().unwrap(),
+        ];
+        main_parse_arguments(args);
+
+        let core_info = fs::read_to_string(generated_code_folder.path().join("src/core_info.rs"))
+            .expect("Additional file of target definition not generated");
+        assert!(
+            core_info.contains(r#"pub const VENDOR: &str = "ACME";"#),
+            "Template context {context_file} not available in templates"
+        );
+    }
+}
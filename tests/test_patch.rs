@@ -0,0 +1,39 @@
+mod common;
+use common::*;
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test code generation from SVD patched with svdtools compatible patch file.
+#[test]
+fn compile_generated_patched() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let patch_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_patch.yaml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = [
+        "",
+        xml_path,
+        generated_code_folder.path().to_str().unwrap(),
+        "--patch",
+        patch_path,
+    ];
+    main_parse_arguments(args);
+
+    let timer_code = fs::read_to_string(generated_code_folder.path().join("src/timer.rs"))
+        .expect("Unable to read timer module");
+    assert!(
+        timer_code.contains("fn match_value("),
+        "Register not renamed"
+    );
+    assert!(
+        !timer_code.contains("fn prescale_wr("),
+        "Register not deleted"
+    );
+    assert!(timer_code.contains("fn patched_reg("), "Register not added");
+    assert!(timer_code.contains("FAST"), "Enumerated values not added");
+
+    set_default_features(generated_code_folder.path(), &["all"]);
+    assert_cargo_build(generated_code_folder);
+}
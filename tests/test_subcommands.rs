@@ -0,0 +1,69 @@
+use std::env;
+use std::fs;
+use svd2pac::main_parse_arguments;
+
+/// Test `generate` subcommand is equivalent to command line without subcommand
+#[test]
+fn generate_subcommand() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let destination_folder = generated_code_folder.path().to_str().unwrap();
+    main_parse_arguments(["", "generate", xml_path, destination_folder]);
+    main_parse_arguments(["", "--verify", xml_path, destination_folder]);
+}
+
+#[test]
+fn validate_subcommand() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    main_parse_arguments([
+        "",
+        "validate",
+        "--address-block-check=warning",
+        "--aliased-register-check=warning",
+        xml_path,
+    ]);
+}
+
+/// Aliased registers of simple.xml are errors by default
+#[test]
+#[should_panic]
+fn validate_aliased_registers() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    main_parse_arguments(["", "validate", xml_path]);
+}
+
+#[test]
+fn patch_subcommand() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+    let patch_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple_patch.yaml");
+
+    // Temp folder that should be deleted in case of test success.
+    let output_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let output_path = output_folder.path().join("patched.svd");
+    main_parse_arguments([
+        "",
+        "patch",
+        xml_path,
+        patch_path,
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    let patched_svd = fs::read_to_string(&output_path).expect("Patched SVD not written");
+    assert!(
+        patched_svd.contains("PATCHED_REG"),
+        "Patch not applied to SVD"
+    );
+
+    // Patched SVD is a valid input of other subcommands
+    main_parse_arguments(["", "info", output_path.to_str().unwrap()]);
+    main_parse_arguments(["", "diff", xml_path, output_path.to_str().unwrap()]);
+    main_parse_arguments([
+        "",
+        "diff",
+        "--format=json",
+        xml_path,
+        output_path.to_str().unwrap(),
+    ]);
+}
@@ -0,0 +1,79 @@
+use clap::Parser;
+use std::env;
+use svd2pac::{main_with_transforms, svd, Args, Transform};
+
+/// Rename a peripheral
+struct RenamePeripheral;
+
+impl Transform for RenamePeripheral {
+    fn apply(&self, device: &mut svd::Device) -> anyhow::Result<()> {
+        for peripheral in device.peripherals.iter_mut() {
+            if peripheral.name == "FOO" {
+                peripheral.name = "RENAMED_FOO".to_owned();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Test that user transformations are applied before code generation.
+#[test]
+fn apply_user_transforms() {
+    let xml_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_svd/simple.xml");
+
+    // Temp folder that should be deleted in case of test success.
+    let generated_code_folder = tempfile::tempdir_in(env::current_dir().unwrap()).unwrap();
+    let args = Args::parse_from(["", xml_path, generated_code_folder.path().to_str().unwrap()]);
+    let remove_uart = |device: &mut svd::Device| -> anyhow::Result<()> {
+        device
+            .peripherals
+            .retain(|peripheral| !peripheral.name.starts_with("UART"));
+        Ok(())
+    };
+    // Size and reset value of added register are inherited from device
+    let add_register = |device: &mut svd::Device| -> anyhow::Result<()> {
+        let peripheral = device
+            .peripherals
+            .iter_mut()
+            .find(|peripheral| peripheral.name == "RENAMED_FOO")
+            .ok_or_else(|| anyhow::anyhow!("Not found peripheral RENAMED_FOO"))?;
+        let register = svd::RegisterInfo::builder()
+            .name("ADDED".to_owned())
+            .address_offset(0x80)
+            .build(svd::ValidateLevel::Strict)?
+            .single();
+        peripheral
+            .registers
+            .get_or_insert_with(Vec::new)
+            .push(svd::RegisterCluster::Register(register));
+        Ok(())
+    };
+    main_with_transforms(
+        args,
+        vec![
+            Box::new(RenamePeripheral),
+            Box::new(remove_uart),
+            Box::new(add_register),
+        ],
+    );
+
+    let src_path = generated_code_folder.path().join("src");
+    assert!(
+        src_path.join("renamed_foo.rs").exists(),
+        "Peripheral not renamed by transformation"
+    );
+    assert!(
+        !src_path.join("foo.rs").exists(),
+        "Module of renamed peripheral still generated"
+    );
+    let foo_code = std::fs::read_to_string(src_path.join("renamed_foo.rs"))
+        .expect("Unable to read renamed_foo.rs");
+    assert!(
+        foo_code.contains("fn added("),
+        "Register not added by transformation"
+    );
+    assert!(
+        !src_path.join("uart.rs").exists(),
+        "Peripheral not removed by transformation"
+    );
+}